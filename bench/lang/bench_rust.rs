@@ -1,33 +1,20 @@
 // Cross-language benchmark: Rust — BTreeMap (B-tree) vs FAST FFI.
 //
-// Compile:
-//   rustc -O --edition 2021 -L ../../build -l fast bench_rust.rs -o bench_rust
+// Uses the fast-tree binding with its `bench-helpers` feature, so the FFI is
+// declared once, in bindings/rust. Compile (see compile_rust in
+// lang_report.py):
+//   rustc -O --edition 2021 --crate-type rlib --crate-name fast_tree \
+//         --cfg 'feature="std"' --cfg 'feature="bench-helpers"' \
+//         --cfg 'feature="test-util"' \
+//         ../../bindings/rust/src/lib.rs -o libfast_tree.rlib
+//   rustc -O --edition 2021 --extern fast_tree=libfast_tree.rlib \
+//         -L ../../build -l fast bench_rust.rs -o bench_rust
 
 use std::collections::BTreeMap;
 use std::time::Instant;
 
-// Inline FFI declarations (avoids Cargo dependency)
-#[repr(C)]
-struct FastTreeOpaque {
-    _private: [u8; 0],
-}
-
-extern "C" {
-    fn fast_create(keys: *const i32, n: usize) -> *mut FastTreeOpaque;
-    fn fast_destroy(tree: *mut FastTreeOpaque);
-    fn fast_search(tree: *const FastTreeOpaque, key: i32) -> i64;
-}
-
-fn emit_json(compiler: &str, method: &str, tree_size: usize, num_queries: usize, sec: f64) {
-    let mqs = num_queries as f64 / sec / 1e6;
-    let nsq = sec * 1e9 / num_queries as f64;
-    println!(
-        "{{\"language\":\"rust\",\"compiler\":\"{}\",\"method\":\"{}\",\
-         \"tree_size\":{},\"num_queries\":{},\
-         \"total_sec\":{:.4},\"mqs\":{:.2},\"ns_per_query\":{:.1}}}",
-        compiler, method, tree_size, num_queries, sec, mqs, nsq
-    );
-}
+use fast_tree::bench::{lcg_queries, run_search_benchmark, time_queries, BenchResult};
+use fast_tree::FastTree;
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
@@ -40,36 +27,24 @@ fn main() {
     let keys: Vec<i32> = (0..tree_size).map(|i| (i as i32) * 3 + 1).collect();
     let max_key = keys[tree_size - 1];
 
-    // Generate random queries (simple LCG seeded with 42)
-    let mut rng_state: u64 = 42;
-    let queries: Vec<i32> = (0..num_queries)
-        .map(|_| {
-            rng_state = rng_state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
-            ((rng_state >> 33) as i32).rem_euclid(max_key + 1)
-        })
-        .collect();
-
-    let warmup = num_queries.min(100_000);
+    // Random queries from the shared LCG seeded with 42
+    let queries = lcg_queries(42, num_queries, max_key);
 
     // --- FAST FFI ---
-    unsafe {
-        let tree = fast_create(keys.as_ptr(), keys.len());
-        assert!(!tree.is_null());
-
-        let mut sink: i64 = 0;
-        for i in 0..warmup {
-            sink = sink.wrapping_add(fast_search(tree, queries[i]));
-        }
+    {
+        let tree = FastTree::new(&keys).expect("fast_create failed");
+        let r = run_search_benchmark(&tree, &queries);
+        println!("{}", r.to_json(compiler, "fast_ffi"));
 
+        // Same queries through one batch call
+        let warmup = num_queries.min(100_000);
+        std::hint::black_box(tree.search_batch_ffi(&queries[..warmup]));
         let t0 = Instant::now();
-        for i in 0..num_queries {
-            sink = sink.wrapping_add(fast_search(tree, queries[i]));
-        }
-        let elapsed = t0.elapsed().as_secs_f64();
-        emit_json(compiler, "fast_ffi", tree_size, num_queries, elapsed);
-
-        fast_destroy(tree);
-        std::hint::black_box(sink);
+        let out = tree.search_batch_ffi(&queries);
+        let total_sec = t0.elapsed().as_secs_f64();
+        std::hint::black_box(out);
+        let r = BenchResult { tree_size, num_queries, total_sec };
+        println!("{}", r.to_json(compiler, "fast_ffi_batch"));
     }
 
     // --- BTreeMap (B-tree) ---
@@ -79,24 +54,9 @@ fn main() {
             btree.insert(k, i);
         }
 
-        let mut sink: i64 = 0;
-        for i in 0..warmup {
-            let idx = btree.range(..=queries[i]).next_back()
-                .map(|(_, &v)| v as i64)
-                .unwrap_or(-1);
-            sink = sink.wrapping_add(idx);
-        }
-
-        let t0 = Instant::now();
-        for i in 0..num_queries {
-            let idx = btree.range(..=queries[i]).next_back()
-                .map(|(_, &v)| v as i64)
-                .unwrap_or(-1);
-            sink = sink.wrapping_add(idx);
-        }
-        let elapsed = t0.elapsed().as_secs_f64();
-        emit_json(compiler, "BTreeMap", tree_size, num_queries, elapsed);
-
-        std::hint::black_box(sink);
+        let r = time_queries(tree_size, &queries, |q| {
+            btree.range(..=q).next_back().map(|(_, &v)| v as i64).unwrap_or(-1)
+        });
+        println!("{}", r.to_json(compiler, "BTreeMap"));
     }
 }
@@ -215,12 +215,9 @@ def get_compile_rules(root, build_dir, bench_build):
         "run": [str(bench_build / "bench_cpp_clang"), "{tree_size}", "{num_queries}"],
         "env": env_ld,
     }
-    # Rust
+    # Rust (two-step: build the fast-tree binding, then the benchmark)
     rules[("rust", "rustc")] = {
-        "compile": ["rustc", "-O", "--edition", "2021",
-                     f"-L{lib}", "-l", "fast",
-                     str(LANG_DIR / "bench_rust.rs"),
-                     "-o", str(bench_build / "bench_rust")],
+        "compile": "rust_special",  # handled separately
         "run": [str(bench_build / "bench_rust"), "{tree_size}", "{num_queries}"],
         "env": env_ld,
     }
@@ -462,6 +459,36 @@ def compile_java(root, build_dir, bench_build, toolchains):
     return True
 
 
+def compile_rust(root, build_dir, bench_build):
+    """Build the fast-tree crate as an rlib, then the benchmark against it."""
+    lib = str(root / build_dir)
+    rlib = bench_build / "libfast_tree.rlib"
+    steps = [
+        ["rustc", "-O", "--edition", "2021", "--crate-type", "rlib",
+         "--crate-name", "fast_tree",
+         "--cfg", 'feature="std"', "--cfg", 'feature="bench-helpers"',
+         "--cfg", 'feature="test-util"',
+         str(root / "bindings" / "rust" / "src" / "lib.rs"),
+         "-o", str(rlib)],
+        ["rustc", "-O", "--edition", "2021",
+         "--extern", f"fast_tree={rlib}", f"-L{lib}", "-l", "fast",
+         str(LANG_DIR / "bench_rust.rs"),
+         "-o", str(bench_build / "bench_rust")],
+    ]
+    try:
+        for cmd in steps:
+            proc = subprocess.run(cmd, capture_output=True, text=True, timeout=120)
+            if proc.returncode != 0:
+                print(f" FAILED")
+                print(f"    {proc.stderr[:300]}", file=sys.stderr)
+                return False
+        print(" OK")
+        return True
+    except (subprocess.TimeoutExpired, OSError) as e:
+        print(f" ERROR: {e}")
+        return False
+
+
 def compile_mercury(root, build_dir, bench_build):
     """Special compilation for Mercury (mmc --make from source dir)."""
     lib = str(root / build_dir)
@@ -517,6 +544,11 @@ def compile_benchmarks(rules, toolchains, root, build_dir, bench_build):
             compiled[(lang, compiler)] = compile_java(root, build_dir, bench_build, toolchains)
             continue
 
+        if rule["compile"] == "rust_special":
+            print(f"  Compiling rust/rustc (crate + bench)...", end="", flush=True)
+            compiled[(lang, compiler)] = compile_rust(root, build_dir, bench_build)
+            continue
+
         if rule["compile"] == "mercury_special":
             print(f"  Compiling mercury (mmc --make)...")
             compiled[(lang, compiler)] = compile_mercury(
@@ -783,8 +815,8 @@ def _make_bar_chart(results, tree_sizes, out_path):
             if sizes:
                 ts = max(sizes)
                 target_recs = [r for r in recs if r["tree_size"] == ts]
-        fast_recs = [r for r in target_recs if r["method"] == "fast_ffi"]
-        native_recs = [r for r in target_recs if r["method"] != "fast_ffi"]
+        fast_recs = [r for r in target_recs if r["method"].startswith("fast_ffi")]
+        native_recs = [r for r in target_recs if not r["method"].startswith("fast_ffi")]
         if fast_recs and native_recs:
             best_fast = max(fast_recs, key=lambda r: r.get("mqs", 0))
             best_native = max(native_recs, key=lambda r: r.get("mqs", 0))
@@ -846,8 +878,8 @@ def _make_speedup_chart(results, tree_sizes, out_path):
             if sizes:
                 ts = max(sizes)
                 target_recs = [r for r in recs if r["tree_size"] == ts]
-        fast_recs = [r for r in target_recs if r["method"] == "fast_ffi"]
-        native_recs = [r for r in target_recs if r["method"] != "fast_ffi"]
+        fast_recs = [r for r in target_recs if r["method"].startswith("fast_ffi")]
+        native_recs = [r for r in target_recs if not r["method"].startswith("fast_ffi")]
         if fast_recs and native_recs:
             best_fast = max(fast_recs, key=lambda r: r.get("mqs", 0))
             best_native = max(native_recs, key=lambda r: r.get("mqs", 0))
@@ -915,7 +947,7 @@ def _make_grid_chart(results, out_path):
     ci = [0]
 
     def get_method_color(method):
-        if method == "fast_ffi":
+        if method.startswith("fast_ffi"):
             return COLOR_FAST
         if method not in method_colors:
             method_colors[method] = color_cycle[ci[0] % len(color_cycle)]
@@ -947,7 +979,7 @@ def _make_grid_chart(results, out_path):
             x_pos = [size_to_idx[s] for s in sorted_sizes]
             mqs = [best_by_size[s] for s in sorted_sizes]
             color = get_method_color(method)
-            ls = "-" if method == "fast_ffi" else "--"
+            ls = "-" if method.startswith("fast_ffi") else "--"
             ax.plot(x_pos, mqs, f"o{ls}", color=color,
                     label=method, markersize=4, linewidth=1.5)
         ax.set_xlim(-0.3, n_ticks - 0.7)
@@ -1105,7 +1137,7 @@ def render_latex(results, sys_info, toolchains, tree_sizes, charts, build_dir):
             "tree_size": fmt_size(r.get("tree_size", 0)),
             "mqs": f"{r.get('mqs', 0):.2f}",
             "ns_per_query": f"{r.get('ns_per_query', 0):.1f}",
-            "is_fast": r.get("method", "") == "fast_ffi",
+            "is_fast": r.get("method", "").startswith("fast_ffi"),
         })
 
     # Build compiler charts list
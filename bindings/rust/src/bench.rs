@@ -0,0 +1,243 @@
+//! Benchmark helpers, enabled by the `bench-helpers` feature.
+//!
+//! These are what `bench/lang/bench_rust.rs` uses, so the cross-language
+//! benchmark drives the real binding instead of re-declaring the FFI. The
+//! query generator and JSON output match the other languages' benchmarks.
+
+use std::time::Instant;
+
+use crate::tiny::TinyKeys;
+use crate::{query_gen, test_util, FastTree};
+
+/// Queries run untimed before each measurement, to warm caches and
+/// branch predictors.
+const WARMUP_QUERIES: usize = 100_000;
+
+/// Generate `count` queries uniformly in `0..=max_key` with the LCG every
+/// language benchmark uses, so equal seeds give equal query streams across
+/// languages. Same as [`query_gen::uniform`], which also offers skewed
+/// distributions.
+pub fn lcg_queries(seed: u64, count: usize, max_key: i32) -> Vec<i32> {
+    query_gen::uniform(seed, count, max_key)
+}
+
+/// Timing of one benchmark run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchResult {
+    /// Number of keys in the tree searched.
+    pub tree_size: usize,
+    /// Number of timed queries.
+    pub num_queries: usize,
+    /// Wall-clock time for the timed queries, in seconds.
+    pub total_sec: f64,
+}
+
+impl BenchResult {
+    /// Throughput in millions of queries per second.
+    pub fn mqs(&self) -> f64 {
+        self.num_queries as f64 / self.total_sec / 1e6
+    }
+
+    /// Mean latency in nanoseconds per query.
+    pub fn ns_per_query(&self) -> f64 {
+        self.total_sec * 1e9 / self.num_queries as f64
+    }
+
+    /// One JSON record in the shape `bench/lang_report.py` reads.
+    pub fn to_json(&self, compiler: &str, method: &str) -> String {
+        format!(
+            "{{\"language\":\"rust\",\"compiler\":\"{}\",\"method\":\"{}\",\
+             \"tree_size\":{},\"num_queries\":{},\
+             \"total_sec\":{:.4},\"mqs\":{:.2},\"ns_per_query\":{:.1}}}",
+            compiler,
+            method,
+            self.tree_size,
+            self.num_queries,
+            self.total_sec,
+            self.mqs(),
+            self.ns_per_query()
+        )
+    }
+}
+
+/// Time `query` over every key in `queries`, after a short untimed warmup.
+///
+/// `query` returns an `i64` that is summed into a sink the optimizer cannot
+/// see through, so the work cannot be elided. Use this for competitors
+/// (say a `BTreeMap`) to time them exactly as the FAST tree is timed.
+pub fn time_queries<F: FnMut(i32) -> i64>(
+    tree_size: usize,
+    queries: &[i32],
+    mut query: F,
+) -> BenchResult {
+    let mut sink: i64 = 0;
+    for &q in &queries[..queries.len().min(WARMUP_QUERIES)] {
+        sink = sink.wrapping_add(query(q));
+    }
+
+    let t0 = Instant::now();
+    for &q in queries {
+        sink = sink.wrapping_add(query(q));
+    }
+    let total_sec = t0.elapsed().as_secs_f64();
+    std::hint::black_box(sink);
+
+    BenchResult {
+        tree_size,
+        num_queries: queries.len(),
+        total_sec,
+    }
+}
+
+/// Time [`FastTree::search`] over `queries`.
+pub fn run_search_benchmark(tree: &FastTree, queries: &[i32]) -> BenchResult {
+    time_queries(tree.size(), queries, |q| tree.search(q).map_or(-1, |i| i as i64))
+}
+
+/// Time [`FastTree::search`] and
+/// [`interpolation_search`](test_util::interpolation_search) over the same
+/// `queries` on the tree's keys, returning `(fast, interpolation)`.
+///
+/// Both runs go through [`time_queries`] with identical warmup, so the two
+/// results compare directly; feed skewed streams from
+/// [`query_gen`] to see where interpolation falls behind.
+pub fn run_interpolation_comparison(
+    tree: &FastTree,
+    queries: &[i32],
+) -> (BenchResult, BenchResult) {
+    let keys = tree.sorted_keys();
+    let fast = run_search_benchmark(tree, queries);
+    let interpolation = time_queries(tree.size(), queries, |q| {
+        test_util::interpolation_search(keys, q).map_or(-1, |i| i as i64)
+    });
+    (fast, interpolation)
+}
+
+/// The Rust scan used for tiny trees and the FFI search, timed on one tree
+/// size by [`run_tiny_crossover`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TinyCrossover {
+    /// Number of keys, which is also the width of the scan.
+    pub size: usize,
+    /// The fixed-width Rust scan `search` uses for trees of up to 16 keys.
+    pub scan: BenchResult,
+    /// The C library's search on the same keys.
+    pub ffi: BenchResult,
+}
+
+/// Time the tiny-tree scan against the FFI search at 4, 8, 16, 32 and 64
+/// keys, the last two past the 16-key cutoff, to show where the C search
+/// takes over. The keys are spread evenly over the range of `queries`.
+pub fn run_tiny_crossover(queries: &[i32]) -> Vec<TinyCrossover> {
+    vec![
+        tiny_crossover_at::<4>(queries),
+        tiny_crossover_at::<8>(queries),
+        tiny_crossover_at::<16>(queries),
+        tiny_crossover_at::<32>(queries),
+        tiny_crossover_at::<64>(queries),
+    ]
+}
+
+fn tiny_crossover_at<const N: usize>(queries: &[i32]) -> TinyCrossover {
+    let max = queries.iter().copied().max().unwrap_or(0).max(0);
+    let step = max / N as i32 + 1;
+    let keys: Vec<i32> = (0..N as i32).map(|i| i * step).collect();
+    let tree = FastTree::new(&keys).unwrap();
+    let scan = TinyKeys::<i32, N>::new(&keys).unwrap();
+    TinyCrossover {
+        size: N,
+        scan: time_queries(N, queries, |q| scan.search(q).map_or(-1, |i| i as i64)),
+        ffi: time_queries(N, queries, |q| tree.search_ffi(q).map_or(-1, |i| i as i64)),
+    }
+}
+
+/// [`FastTree::contains`] against the bloom-filtered
+/// [`contains_maybe`](crate::FastTreeBloom::contains_maybe) on one query
+/// stream, timed by [`run_bloom_comparison`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BloomComparison {
+    /// Whether every query is a key of the tree (otherwise none is).
+    pub all_hit: bool,
+    /// Plain `contains` on the tree.
+    pub plain: BenchResult,
+    /// `contains_maybe` through the filter.
+    pub bloom: BenchResult,
+}
+
+/// Time `contains` against `contains_maybe` on an all-miss and an all-hit
+/// stream, in that order, to show when the filter pays for itself.
+///
+/// The tree holds the even keys `0, 2, ..., 2 * max`, where `max` is the
+/// largest of `queries`; each query `q` becomes `2 * q` for the hits and
+/// `2 * q + 1` for the misses, so both streams visit the same places.
+pub fn run_bloom_comparison(queries: &[i32]) -> Vec<BloomComparison> {
+    let max = queries.iter().copied().max().unwrap_or(0).max(0);
+    let keys: Vec<i32> = (0..=max).map(|i| i * 2).collect();
+    let tree = FastTree::with_bloom(&keys).unwrap();
+    [false, true]
+        .into_iter()
+        .map(|all_hit| {
+            let stream: Vec<i32> = queries.iter().map(|&q| q * 2 + i32::from(!all_hit)).collect();
+            BloomComparison {
+                all_hit,
+                plain: time_queries(keys.len(), &stream, |q| i64::from(tree.contains(q))),
+                bloom: time_queries(keys.len(), &stream, |q| i64::from(tree.contains_maybe(q))),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_benchmark_reports_counts() {
+        let keys: Vec<i32> = (0..1000).map(|i| i * 3 + 1).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        let queries = lcg_queries(42, 5000, 2998);
+        let r = run_search_benchmark(&tree, &queries);
+        assert_eq!((r.tree_size, r.num_queries), (1000, 5000));
+        assert!(r.total_sec > 0.0);
+        let json = r.to_json("rustc", "fast_ffi");
+        assert!(json.starts_with(
+            "{\"language\":\"rust\",\"compiler\":\"rustc\",\"method\":\"fast_ffi\","
+        ));
+        assert!(json.contains("\"tree_size\":1000,\"num_queries\":5000,"));
+    }
+
+    #[test]
+    fn tiny_crossover_covers_the_cutoff() {
+        let queries = lcg_queries(9, 2000, 10_000);
+        let rows = run_tiny_crossover(&queries);
+        let sizes: Vec<usize> = rows.iter().map(|r| r.size).collect();
+        assert_eq!(sizes, [4, 8, 16, 32, 64]);
+        for r in &rows {
+            assert_eq!((r.scan.num_queries, r.ffi.num_queries), (2000, 2000));
+            assert!(r.scan.total_sec > 0.0 && r.ffi.total_sec > 0.0);
+        }
+    }
+
+    #[test]
+    fn bloom_comparison_misses_then_hits() {
+        let queries = lcg_queries(5, 2000, 10_000);
+        let rows = run_bloom_comparison(&queries);
+        let size = *queries.iter().max().unwrap() as usize + 1;
+        assert_eq!(rows.iter().map(|r| r.all_hit).collect::<Vec<_>>(), [false, true]);
+        for r in &rows {
+            assert_eq!((r.plain.tree_size, r.bloom.num_queries), (size, 2000));
+            assert!(r.plain.total_sec > 0.0 && r.bloom.total_sec > 0.0);
+        }
+    }
+
+    #[test]
+    fn interpolation_comparison_times_both() {
+        let keys: Vec<i32> = (0..1000).map(|i| i * i).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        let queries = lcg_queries(3, 2000, 999 * 999);
+        let (fast, interp) = run_interpolation_comparison(&tree, &queries);
+        assert_eq!((fast.num_queries, interp.num_queries), (2000, 2000));
+        assert_eq!(fast.tree_size, interp.tree_size);
+        assert!(fast.total_sec > 0.0 && interp.total_sec > 0.0);
+    }
+}
@@ -0,0 +1,177 @@
+//! A bloom-filter prefilter in front of a [`FastTree`], for membership
+//! tests that mostly miss.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+
+use crate::{FastKey, FastTree};
+
+/// Filter bits per key. With the optimal probe count for this size
+/// ([`PROBES`]) the false-positive rate is about 0.8%.
+const BITS_PER_KEY: usize = 10;
+
+/// Bits probed per key, `BITS_PER_KEY * ln 2` rounded.
+const PROBES: u32 = 7;
+
+/// A [`FastTree`] paired with a bloom filter over its keys.
+///
+/// [`contains_maybe`](FastTreeBloom::contains_maybe) rejects most absent
+/// keys after a few bit tests in a small array, without descending the
+/// tree; only keys that pass the filter (all members, plus the false
+/// positives) pay for a search. The filter costs 10 bits per key, with
+/// the total rounded up to a power of two: between 1.25 and 2.5 bytes per
+/// key, beyond a minimum of one 64-bit word. The false-positive rate for
+/// `b` bits per key is roughly `0.6185^b`:
+///
+/// | bits per key | false positives |
+/// |--------------|-----------------|
+/// | 8            | ~2.1%           |
+/// | 10           | ~0.8%           |
+/// | 16           | ~0.05%          |
+///
+/// Rounding the size up to a power of two only lowers the rate. A miss
+/// the filter rejects skips the tree traversal, so the filter pays off
+/// when most queries miss; for workloads that mostly hit, every query
+/// probes both, and plain [`contains`](FastTree::contains) does less work.
+/// `bench::run_bloom_comparison` (feature `bench-helpers`) measures both
+/// cases. On one x86-64 machine with 5M uniform queries, all-miss lookups
+/// took 12-24 ns against 74-259 ns for `contains` (6-11x faster, 1K to 4M
+/// keys), while all-hit lookups were 8-18% slower than `contains`.
+///
+/// It dereferences to [`FastTree`], so every query method is available.
+pub struct FastTreeBloom<K: FastKey = i32> {
+    tree: FastTree<K>,
+    bits: Vec<u64>,
+}
+
+impl<K: FastKey> FastTree<K> {
+    /// Build a tree from sorted `keys` together with a bloom filter over
+    /// them. Returns `None` under the same conditions as
+    /// [`new`](FastTree::new).
+    pub fn with_bloom(keys: &[K]) -> Option<FastTreeBloom<K>> {
+        let tree = FastTree::new(keys)?;
+        let words = (keys.len() * BITS_PER_KEY).div_ceil(64).next_power_of_two();
+        let mut filter = FastTreeBloom {
+            tree,
+            bits: vec![0; words],
+        };
+        let mask = filter.bits.len() * 64 - 1;
+        for &key in keys {
+            for bit in bit_positions(key, mask) {
+                filter.bits[bit / 64] |= 1 << (bit % 64);
+            }
+        }
+        Some(filter)
+    }
+}
+
+impl<K: FastKey> FastTreeBloom<K> {
+    /// Whether `key` is in the tree, answered from the filter alone when it
+    /// rules the key out.
+    ///
+    /// Despite the name the answer is exact: keys the filter cannot rule
+    /// out fall back to [`contains`](FastTree::contains). There are no
+    /// false negatives.
+    pub fn contains_maybe(&self, key: K) -> bool {
+        self.may_contain(key) && self.tree.contains(key)
+    }
+
+    /// The filter's verdict alone: `false` means `key` is definitely
+    /// absent, `true` means it may be present.
+    pub fn may_contain(&self, key: K) -> bool {
+        let mask = self.bits.len() * 64 - 1;
+        bit_positions(key, mask).all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    /// Bytes used by the filter, on top of the tree's
+    /// [`memory_usage`](FastTree::memory_usage).
+    pub fn filter_bytes(&self) -> usize {
+        self.bits.len() * 8
+    }
+
+    /// Unwrap into the underlying tree, dropping the filter.
+    pub fn into_inner(self) -> FastTree<K> {
+        self.tree
+    }
+}
+
+impl<K: FastKey> Deref for FastTreeBloom<K> {
+    type Target = FastTree<K>;
+
+    fn deref(&self) -> &FastTree<K> {
+        &self.tree
+    }
+}
+
+/// The `PROBES` filter bits for `key`, derived by double hashing from one
+/// 64-bit hash; `mask` is the filter size in bits minus one.
+fn bit_positions<K: Hash>(key: K, mask: usize) -> impl Iterator<Item = usize> {
+    let h = hash(key);
+    let step = (h >> 32) | 1;
+    (0..u64::from(PROBES)).map(move |i| (h.wrapping_add(i.wrapping_mul(step)) as usize) & mask)
+}
+
+/// 64-bit FNV-1a, finished with a multiply-xorshift so nearby integer keys
+/// spread over the whole filter.
+struct KeyHasher(u64);
+
+impl Hasher for KeyHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ u64::from(b)).wrapping_mul(0x100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        let mut h = self.0;
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        h ^= h >> 33;
+        h
+    }
+}
+
+fn hash<K: Hash>(key: K) -> u64 {
+    let mut hasher = KeyHasher(0xcbf2_9ce4_8422_2325);
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FastTree, FastTree64};
+
+    #[test]
+    fn no_false_negatives() {
+        let keys: Vec<i32> = (0..20_000).map(|i| i * 7 - 30_000).collect();
+        let tree = FastTree::with_bloom(&keys).unwrap();
+        assert!(keys.iter().all(|&k| tree.may_contain(k) && tree.contains_maybe(k)));
+        assert_eq!(tree.search(0), tree.sorted_keys().iter().rposition(|&k| k <= 0));
+
+        let wide = FastTree64::with_bloom(&[i64::MIN, -1, 1 << 50, i64::MAX]).unwrap();
+        assert!(wide.contains_maybe(i64::MIN) && wide.contains_maybe(1 << 50));
+        assert!(!wide.contains_maybe(0));
+    }
+
+    #[test]
+    fn false_positive_rate_near_target() {
+        let keys: Vec<i32> = (0..50_000).map(|i| i * 4).collect();
+        let tree = FastTree::with_bloom(&keys).unwrap();
+        let misses = (0..200_000).map(|i| i * 4 + 1);
+        let false_positives = misses.clone().filter(|&k| tree.may_contain(k)).count();
+        // About 0.8% at 10 bits per key; allow slack for the hash.
+        assert!(false_positives < 200_000 / 50, "{false_positives} false positives");
+        assert!(misses.take(1000).all(|k| !tree.contains_maybe(k)));
+        assert!(tree.filter_bytes() * 8 >= keys.len() * 10);
+        assert!(tree.filter_bytes() * 2 <= keys.len() * 5);
+        assert_eq!(tree.into_inner().size(), keys.len());
+    }
+
+    #[test]
+    fn rejects_like_new() {
+        assert!(FastTree::<i32>::with_bloom(&[]).is_none());
+        assert!(FastTree::with_bloom(&[2, 1]).is_none());
+    }
+}
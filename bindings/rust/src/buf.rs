@@ -0,0 +1,129 @@
+//! Accumulate keys in any order, then freeze them into a tree.
+
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+use crate::{FastKey, FastTree, FastTreeBuilder, FastTreeError};
+
+/// A growable key buffer that compiles to a [`FastTree`] once filled.
+///
+/// It behaves like a `Vec`: keys are pushed in any order, duplicates
+/// included, and the buffer derefs to the slice pushed so far. The
+/// [`freeze`](FastTreeBuf::freeze) step sorts the keys and builds the
+/// tree, modelling the usual "accumulate, then index" workflow:
+///
+/// ```no_run
+/// use fast_tree::FastTreeBuf;
+/// let mut buf = FastTreeBuf::new();
+/// for k in [30, 10, 20, 10] {
+///     buf.push(k);
+/// }
+/// let tree = buf.freeze().unwrap();
+/// assert_eq!(tree.sorted_keys(), [10, 10, 20, 30]);
+/// ```
+///
+/// For control over sorting, and to reuse one buffer across several
+/// trees, use [`FastTreeBuilder`] instead.
+#[derive(Debug, Clone, Default)]
+pub struct FastTreeBuf<K: FastKey = i32> {
+    keys: Vec<K>,
+}
+
+impl<K: FastKey> FastTreeBuf<K> {
+    /// An empty buffer.
+    pub fn new() -> Self {
+        FastTreeBuf { keys: Vec::new() }
+    }
+
+    /// An empty buffer with room for `capacity` keys.
+    pub fn with_capacity(capacity: usize) -> Self {
+        FastTreeBuf {
+            keys: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Add one key, in any order.
+    pub fn push(&mut self, key: K) {
+        self.keys.push(key);
+    }
+
+    /// Sort the keys and build the tree.
+    ///
+    /// Returns [`FastTreeError::Empty`] if nothing was pushed.
+    pub fn freeze(self) -> Result<FastTree<K>, FastTreeError> {
+        FastTreeBuilder::new(self.keys).build()
+    }
+
+    /// Like [`freeze`](FastTreeBuf::freeze), keeping one copy of each
+    /// distinct key.
+    pub fn freeze_dedup(self) -> Result<FastTree<K>, FastTreeError> {
+        FastTreeBuilder::new(self.keys).with_dedup(true).build()
+    }
+
+    /// The keys pushed so far, in push order.
+    pub fn into_vec(self) -> Vec<K> {
+        self.keys
+    }
+}
+
+impl<K: FastKey> Deref for FastTreeBuf<K> {
+    type Target = [K];
+
+    fn deref(&self) -> &[K] {
+        &self.keys
+    }
+}
+
+impl<K: FastKey> Extend<K> for FastTreeBuf<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        self.keys.extend(iter);
+    }
+}
+
+impl<K: FastKey> FromIterator<K> for FastTreeBuf<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        FastTreeBuf {
+            keys: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FastTreeBuf;
+    use crate::FastTreeError;
+
+    #[test]
+    fn push_then_freeze() {
+        let mut buf = FastTreeBuf::with_capacity(4);
+        for k in [7, -3, 7, 0] {
+            buf.push(k);
+        }
+        assert_eq!(buf.len(), 4);
+        assert_eq!(&buf[..], [7, -3, 7, 0]);
+        let tree = buf.freeze().unwrap();
+        assert_eq!(tree.sorted_keys(), [-3, 0, 7, 7]);
+        assert_eq!(tree.search(5), Some(1));
+        assert!(tree.contains(7));
+    }
+
+    #[test]
+    fn extend_and_collect() {
+        let mut buf: FastTreeBuf = (0..100).rev().collect();
+        buf.extend([50, 200]);
+        let keys = buf.clone().into_vec();
+        assert_eq!((keys[0], keys.len()), (99, 102));
+        let tree = buf.freeze_dedup().unwrap();
+        assert_eq!(tree.size(), 101);
+        assert_eq!(tree.lower_bound(150), 100);
+        assert_eq!(tree.search(150), Some(99));
+
+        let wide: FastTreeBuf<i64> = [1 << 40, -1].into_iter().collect();
+        assert_eq!(wide.freeze().unwrap().sorted_keys(), [-1, 1 << 40]);
+    }
+
+    #[test]
+    fn empty_buffer_fails() {
+        assert_eq!(FastTreeBuf::<i32>::new().freeze().unwrap_err(), FastTreeError::Empty);
+    }
+}
@@ -0,0 +1,163 @@
+//! A builder that cleans up key input before constructing a tree.
+
+use alloc::vec::Vec;
+
+use crate::{FastKey, FastTree, FastTreeError};
+
+/// Collects keys, optionally sorts and deduplicates them, then builds a
+/// [`FastTree`].
+///
+/// Sorting is on and deduplication off by default, so unordered input
+/// builds without further configuration:
+///
+/// ```no_run
+/// use fast_tree::FastTreeBuilder;
+/// let tree = FastTreeBuilder::new(vec![5, 1, 3, 3])
+///     .with_dedup(true)
+///     .build()
+///     .unwrap();
+/// assert_eq!(tree.sorted_keys(), [1, 3, 5]);
+/// ```
+///
+/// One builder can build many trees in turn: [`reset`](FastTreeBuilder::reset)
+/// empties it but keeps its allocation, so per-partition indexes built in a
+/// loop reuse a single key buffer.
+#[derive(Debug, Clone)]
+pub struct FastTreeBuilder<K: FastKey = i32> {
+    keys: Vec<K>,
+    sort: bool,
+    dedup: bool,
+}
+
+impl<K: FastKey> FastTreeBuilder<K> {
+    /// Start a builder that owns `keys`.
+    pub fn new(keys: Vec<K>) -> Self {
+        FastTreeBuilder {
+            keys,
+            sort: true,
+            dedup: false,
+        }
+    }
+
+    /// Start an empty builder with room for `capacity` keys, to be filled
+    /// with [`push`](FastTreeBuilder::push) or `extend`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::new(Vec::with_capacity(capacity))
+    }
+
+    /// Add one key.
+    pub fn push(&mut self, key: K) {
+        self.keys.push(key);
+    }
+
+    /// Drop all keys, keeping the allocation and the sort and dedup
+    /// settings, ready for the next tree.
+    pub fn reset(&mut self) {
+        self.keys.clear();
+    }
+
+    /// Remove consecutive duplicate keys before building (after sorting, if
+    /// enabled, so all duplicates are consecutive).
+    pub fn with_dedup(mut self, dedup: bool) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Sort the keys before building. With sorting off the keys must
+    /// already be in order, and `build` checks that they are.
+    pub fn with_sort(mut self, sort: bool) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Process the keys in place and build the tree.
+    ///
+    /// Returns [`FastTreeError::Unsorted`] if sorting is off and the keys
+    /// are out of order, and [`FastTreeError::Empty`] if no keys remain.
+    ///
+    /// The builder stays usable: it still holds the processed keys, so a
+    /// second `build` makes an identical tree, and after
+    /// [`reset`](FastTreeBuilder::reset) it can collect keys for another.
+    pub fn build(&mut self) -> Result<FastTree<K>, FastTreeError> {
+        if self.sort {
+            self.keys.sort_unstable();
+        }
+        if self.dedup {
+            self.keys.dedup();
+        }
+        if self.sort {
+            FastTree::build(&self.keys)
+        } else {
+            FastTree::try_new(&self.keys)
+        }
+    }
+}
+
+impl<K: FastKey> Extend<K> for FastTreeBuilder<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        self.keys.extend(iter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FastTreeBuilder;
+    use crate::FastTreeError;
+
+    #[test]
+    fn dedup_removes_duplicates() {
+        let tree = FastTreeBuilder::new(vec![4, 1, 4, 2, 1, 4])
+            .with_dedup(true)
+            .build()
+            .unwrap();
+        assert_eq!(tree.sorted_keys(), [1, 2, 4]);
+
+        let kept = FastTreeBuilder::new(vec![4, 1, 4]).build().unwrap();
+        assert_eq!(kept.sorted_keys(), [1, 4, 4]);
+    }
+
+    #[test]
+    fn sort_off_accepts_presorted_only() {
+        let tree = FastTreeBuilder::new(vec![1, 2, 2, 9])
+            .with_sort(false)
+            .with_dedup(true)
+            .build()
+            .unwrap();
+        assert_eq!(tree.sorted_keys(), [1, 2, 9]);
+
+        let err = FastTreeBuilder::new(vec![2, 1]).with_sort(false).build();
+        assert_eq!(err.err(), Some(FastTreeError::Unsorted));
+    }
+
+    #[test]
+    fn empty_after_processing() {
+        let err = FastTreeBuilder::<i32>::new(Vec::new()).with_dedup(true).build();
+        assert_eq!(err.err(), Some(FastTreeError::Empty));
+        let err = FastTreeBuilder::<i64>::new(Vec::new()).with_sort(false).build();
+        assert_eq!(err.err(), Some(FastTreeError::Empty));
+    }
+
+    #[test]
+    fn reset_reuses_builder() {
+        let mut builder = FastTreeBuilder::with_capacity(64).with_dedup(true);
+        builder.extend([9, 3, 3, 7]);
+        let a = builder.build().unwrap();
+        let cap = builder.keys.capacity();
+
+        builder.reset();
+        for k in (0..40).rev() {
+            builder.push(k * 2);
+        }
+        let b = builder.build().unwrap();
+
+        builder.reset();
+        builder.extend([-5, -5]);
+        let c = builder.build().unwrap();
+
+        assert_eq!(a.sorted_keys(), [3, 7, 9]);
+        assert_eq!(b.size(), 40);
+        assert_eq!(b.search(41), Some(20));
+        assert_eq!(c.sorted_keys(), [-5]);
+        assert_eq!(builder.keys.capacity(), cap);
+    }
+}
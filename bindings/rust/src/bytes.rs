@@ -0,0 +1,150 @@
+//! A byte format for saving a built tree and loading it back.
+//!
+//! Layout (all integers little-endian):
+//!
+//! | offset | size | field                                        |
+//! |--------|------|----------------------------------------------|
+//! | 0      | 4    | magic `b"FAST"`                              |
+//! | 4      | 2    | format version, currently 1                  |
+//! | 6      | 1    | key width in bytes (4 for `i32`, 8 for `i64`) |
+//! | 7      | 1    | reserved, 0                                  |
+//! | 8      | 8    | key count `n`                                |
+//! | 16     | n·w  | the keys in sorted order                     |
+//!
+//! Version 1 stores only the keys and the tree is rebuilt on load. A later
+//! version can append the FAST layout after the keys so loading skips the
+//! rebuild; readers reject versions they do not know. The header is 16
+//! bytes so the key array stays 8-byte aligned when the whole buffer is.
+
+use alloc::vec::Vec;
+
+use crate::{FastKey, FastTree, FastTreeError};
+
+pub(crate) const MAGIC: [u8; 4] = *b"FAST";
+pub(crate) const VERSION: u16 = 1;
+pub(crate) const HEADER_LEN: usize = 16;
+
+/// Append the 16-byte header for `n` keys of type `K` under `magic`.
+pub(crate) fn write_header<K: FastKey>(magic: [u8; 4], n: usize, out: &mut Vec<u8>) {
+    out.extend_from_slice(&magic);
+    out.extend_from_slice(&VERSION.to_le_bytes());
+    out.push(K::WIDTH as u8);
+    out.push(0);
+    out.extend_from_slice(&(n as u64).to_le_bytes());
+}
+
+/// Check the 16-byte header at the front of `data` against `magic` and key
+/// type `K`, and return the key count it declares and the bytes after it.
+pub(crate) fn read_header<K: FastKey>(
+    data: &[u8],
+    magic: [u8; 4],
+) -> Result<(usize, &[u8]), FastTreeError> {
+    if data.len() < HEADER_LEN {
+        return Err(FastTreeError::Truncated);
+    }
+    let (header, body) = data.split_at(HEADER_LEN);
+    if header[..4] != magic
+        || u16::from_le_bytes([header[4], header[5]]) != VERSION
+        || usize::from(header[6]) != K::WIDTH
+        || header[7] != 0
+    {
+        return Err(FastTreeError::InvalidHeader);
+    }
+    let n = u64::from_le_bytes(header[8..16].try_into().unwrap());
+    let n = usize::try_from(n).map_err(|_| FastTreeError::InvalidHeader)?;
+    Ok((n, body))
+}
+
+/// Check a serialized header for key type `K` and return the key bytes
+/// that follow it.
+pub(crate) fn key_bytes<K: FastKey>(data: &[u8]) -> Result<&[u8], FastTreeError> {
+    let (n, body) = read_header::<K>(data, MAGIC)?;
+    let expected = n.checked_mul(K::WIDTH).ok_or(FastTreeError::InvalidHeader)?;
+    if body.len() != expected {
+        return Err(FastTreeError::Truncated);
+    }
+    Ok(body)
+}
+
+impl<K: FastKey> FastTree<K> {
+    /// Serialize the tree: a 16-byte header (magic `b"FAST"`, `u16` format
+    /// version, key width in bytes, a reserved zero byte, `u64` key count)
+    /// followed by the sorted keys, all little-endian.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let keys = self.sorted_keys();
+        let mut out = Vec::with_capacity(HEADER_LEN + keys.len() * K::WIDTH);
+        write_header::<K>(MAGIC, keys.len(), &mut out);
+        for &k in keys {
+            k.write_le(&mut out);
+        }
+        out
+    }
+
+    /// Load a tree written by [`to_bytes`](FastTree::to_bytes).
+    ///
+    /// Returns [`FastTreeError::InvalidHeader`] for a wrong magic, version or
+    /// key width, [`FastTreeError::Truncated`] when the length disagrees with
+    /// the header, and the usual construction errors for empty or unsorted
+    /// keys.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, FastTreeError> {
+        let body = key_bytes::<K>(data)?;
+        let keys: Vec<K> = body.chunks_exact(K::WIDTH).map(K::read_le).collect();
+        Self::try_new(&keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HEADER_LEN;
+    use crate::{FastTree, FastTree64, FastTreeError};
+
+    #[test]
+    fn round_trip() {
+        let keys: Vec<i32> = (0..1000).map(|i| i * 7 - 3000).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        let bytes = tree.to_bytes();
+        assert_eq!(bytes.len(), HEADER_LEN + 4 * keys.len());
+        assert_eq!(&bytes[..4], b"FAST");
+        let back = FastTree::<i32>::from_bytes(&bytes).unwrap();
+        assert_eq!(back, tree);
+        assert_eq!(back.search(1), tree.search(1));
+
+        let wide = FastTree64::new(&[i64::MIN, 1 << 40, i64::MAX]).unwrap();
+        assert_eq!(FastTree64::from_bytes(&wide.to_bytes()).unwrap(), wide);
+    }
+
+    #[test]
+    fn rejects_bad_header() {
+        let bytes = FastTree::new(&[1, 2, 3]).unwrap().to_bytes();
+        let mut bad = bytes.clone();
+        bad[0] = b'X';
+        assert_eq!(FastTree::<i32>::from_bytes(&bad).err(), Some(FastTreeError::InvalidHeader));
+        let mut bad = bytes.clone();
+        bad[4] = 2;
+        assert_eq!(FastTree::<i32>::from_bytes(&bad).err(), Some(FastTreeError::InvalidHeader));
+        // An i32 tree cannot be loaded as an i64 tree.
+        assert_eq!(FastTree64::from_bytes(&bytes).err(), Some(FastTreeError::InvalidHeader));
+    }
+
+    #[test]
+    fn rejects_wrong_length() {
+        let bytes = FastTree::new(&[1, 2, 3]).unwrap().to_bytes();
+        for len in 0..bytes.len() {
+            assert!(FastTree::<i32>::from_bytes(&bytes[..len]).is_err(), "len {len}");
+        }
+        assert_eq!(
+            FastTree::<i32>::from_bytes(&bytes[..bytes.len() - 1]).err(),
+            Some(FastTreeError::Truncated)
+        );
+        let mut long = bytes.clone();
+        long.push(0);
+        assert_eq!(FastTree::<i32>::from_bytes(&long).err(), Some(FastTreeError::Truncated));
+    }
+
+    #[test]
+    fn rejects_unsorted_payload() {
+        let mut bytes = FastTree::new(&[1, 2]).unwrap().to_bytes();
+        bytes[HEADER_LEN..].copy_from_slice(&[2, 0, 0, 0, 1, 0, 0, 0]);
+        assert_eq!(FastTree::<i32>::from_bytes(&bytes).err(), Some(FastTreeError::Unsorted));
+    }
+}
@@ -0,0 +1,183 @@
+//! A delta-encoded byte format for dense key sets.
+//!
+//! Layout: the 16-byte header of [`to_bytes`](crate::FastTree::to_bytes)
+//! with magic `b"FASZ"` (format version 1, key width, reserved zero, `u64`
+//! key count), then the first key as `width` little-endian bytes, then one
+//! unsigned LEB128 varint per remaining key holding its distance from the
+//! key before it. Sorted keys make every delta non-negative, and clustered
+//! keys make most of them fit in a single byte.
+
+use alloc::vec::Vec;
+
+use crate::bytes::{read_header, write_header, HEADER_LEN};
+use crate::{FastKey, FastTree, FastTreeError};
+
+const MAGIC: [u8; 4] = *b"FASZ";
+
+/// Append `v` as an unsigned LEB128 varint.
+fn write_varint(mut v: u64, out: &mut Vec<u8>) {
+    while v >= 0x80 {
+        out.push(v as u8 | 0x80);
+        v >>= 7;
+    }
+    out.push(v as u8);
+}
+
+/// Decode one varint from the front of `data`, advancing it.
+fn read_varint(data: &mut &[u8]) -> Result<u64, FastTreeError> {
+    let mut v = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = data.split_first().ok_or(FastTreeError::Truncated)?;
+        *data = rest;
+        let bits = u64::from(byte & 0x7f);
+        if bits << shift >> shift != bits {
+            return Err(FastTreeError::DeltaOverflow);
+        }
+        v |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Ok(v);
+        }
+    }
+    Err(FastTreeError::DeltaOverflow)
+}
+
+impl<K: FastKey> FastTree<K> {
+    /// Serialize the keys delta-encoded, in the format described at
+    /// [`from_compressed`](FastTree::from_compressed).
+    ///
+    /// Keys that sit close together, such as dense ids or timestamps,
+    /// shrink to about a byte each, against the full key width of
+    /// [`to_bytes`](FastTree::to_bytes). Widely spread keys can take up to
+    /// ten bytes each, so prefer `to_bytes` for those.
+    pub fn to_compressed(&self) -> Vec<u8> {
+        let keys = self.sorted_keys();
+        let mut out = Vec::with_capacity(HEADER_LEN + K::WIDTH + keys.len());
+        write_header::<K>(MAGIC, keys.len(), &mut out);
+        keys[0].write_le(&mut out);
+        for pair in keys.windows(2) {
+            write_varint(pair[1].distance(pair[0]), &mut out);
+        }
+        out
+    }
+
+    /// Load a tree written by [`to_compressed`](FastTree::to_compressed).
+    ///
+    /// The 16-byte header is the [`to_bytes`](FastTree::to_bytes) header
+    /// with magic `b"FASZ"`; the body is the first key at full width
+    /// followed by one unsigned LEB128 varint delta per further key.
+    ///
+    /// Returns [`FastTreeError::InvalidHeader`] for a wrong magic, version
+    /// or key width, [`FastTreeError::Truncated`] when the body ends early
+    /// or runs past the declared key count, [`FastTreeError::DeltaOverflow`]
+    /// when a delta carries a key past the type's maximum, and
+    /// [`FastTreeError::Empty`] for a count of zero.
+    pub fn from_compressed(data: &[u8]) -> Result<Self, FastTreeError> {
+        let (n, mut body) = read_header::<K>(data, MAGIC)?;
+        if n == 0 {
+            return Err(FastTreeError::Empty);
+        }
+        if body.len() < K::WIDTH {
+            return Err(FastTreeError::Truncated);
+        }
+        let (first, rest) = body.split_at(K::WIDTH);
+        body = rest;
+        // Every delta takes at least one byte, which bounds an untrusted
+        // count before it sizes an allocation.
+        let mut keys = Vec::with_capacity(n.min(body.len() + 1));
+        let mut key = K::read_le(first);
+        keys.push(key);
+        for _ in 1..n {
+            let delta = read_varint(&mut body)?;
+            key = key
+                .checked_add_distance(delta)
+                .ok_or(FastTreeError::DeltaOverflow)?;
+            keys.push(key);
+        }
+        if !body.is_empty() {
+            return Err(FastTreeError::Truncated);
+        }
+        Self::build(&keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_varint, write_varint};
+    use crate::{FastTree, FastTree64, FastTreeError};
+
+    #[test]
+    fn round_trip() {
+        let sets: [&[i32]; 4] = [
+            &[-5_000_000, -4_999_999, -3, 0, 0, 0, 17, 1 << 30],
+            &[i32::MIN, i32::MAX],
+            &[i32::MIN; 3],
+            &[42],
+        ];
+        for keys in sets {
+            let tree = FastTree::new(keys).unwrap();
+            assert_eq!(FastTree::<i32>::from_compressed(&tree.to_compressed()).unwrap(), tree);
+        }
+        let wide = FastTree64::new(&[i64::MIN, -1, 1 << 40, i64::MAX]).unwrap();
+        assert_eq!(FastTree64::from_compressed(&wide.to_compressed()).unwrap(), wide);
+    }
+
+    #[test]
+    fn clustered_keys_shrink() {
+        let keys: Vec<i32> = (0..100_000).map(|i| 1_000_000_000 + i * 3 + i % 2).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        let compressed = tree.to_compressed();
+        // One byte per delta after the header and first key.
+        assert_eq!(compressed.len(), 16 + 4 + keys.len() - 1);
+        assert!(compressed.len() * 3 < tree.to_bytes().len());
+        let back = FastTree::<i32>::from_compressed(&compressed).unwrap();
+        assert_eq!(back.sorted_keys(), &keys[..]);
+    }
+
+    #[test]
+    fn varint_edges() {
+        for v in [0, 1, 127, 128, 300, u64::from(u32::MAX), u64::MAX] {
+            let mut buf = Vec::new();
+            write_varint(v, &mut buf);
+            let mut data = &buf[..];
+            assert_eq!(read_varint(&mut data), Ok(v));
+            assert!(data.is_empty());
+        }
+        assert_eq!(read_varint(&mut &[0x80][..]), Err(FastTreeError::Truncated));
+        assert_eq!(read_varint(&mut &[0xff; 10][..]), Err(FastTreeError::DeltaOverflow));
+        let mut too_wide = [0xff; 10];
+        too_wide[9] = 0x02;
+        assert_eq!(read_varint(&mut &too_wide[..]), Err(FastTreeError::DeltaOverflow));
+    }
+
+    #[test]
+    fn rejects_corrupt_input() {
+        let bytes = FastTree::new(&[10, 20, 30]).unwrap().to_compressed();
+        for len in 0..bytes.len() {
+            assert!(FastTree::<i32>::from_compressed(&bytes[..len]).is_err(), "len {len}");
+        }
+        let mut long = bytes.clone();
+        long.push(0);
+        assert_eq!(FastTree::<i32>::from_compressed(&long).err(), Some(FastTreeError::Truncated));
+        let plain = FastTree::new(&[10, 20, 30]).unwrap().to_bytes();
+        assert_eq!(
+            FastTree::<i32>::from_compressed(&plain).err(),
+            Some(FastTreeError::InvalidHeader)
+        );
+        assert_eq!(
+            FastTree64::from_compressed(&bytes).err(),
+            Some(FastTreeError::InvalidHeader)
+        );
+
+        // A delta that carries i32::MAX - 1 past the maximum.
+        let mut overflow = FastTree::new(&[i32::MAX - 1, i32::MAX]).unwrap().to_compressed();
+        *overflow.last_mut().unwrap() = 2;
+        assert_eq!(
+            FastTree::<i32>::from_compressed(&overflow).err(),
+            Some(FastTreeError::DeltaOverflow)
+        );
+
+        let mut empty = bytes[..16].to_vec();
+        empty[8..16].fill(0);
+        assert_eq!(FastTree::<i32>::from_compressed(&empty).err(), Some(FastTreeError::Empty));
+    }
+}
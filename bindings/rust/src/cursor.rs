@@ -0,0 +1,111 @@
+//! A movable position over the sorted keys of a [`FastTree`].
+
+use crate::{FastKey, FastTree};
+
+/// A position in a [`FastTree`]'s sorted keys that can step in either
+/// direction, for scan-and-step algorithms such as merge joins.
+///
+/// The index ranges over `0..=size()`; `size()` is the past-the-end
+/// position, where [`key`](Cursor::key) is `None`. Created by
+/// [`FastTree::cursor_at`].
+#[derive(Clone, Copy)]
+pub struct Cursor<'a, K: FastKey = i32> {
+    tree: &'a FastTree<K>,
+    index: usize,
+}
+
+impl<'a, K: FastKey> Cursor<'a, K> {
+    pub(crate) fn new(tree: &'a FastTree<K>, index: usize) -> Self {
+        Cursor { tree, index }
+    }
+
+    /// The sorted index the cursor is at, in `0..=size()`.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The key at the cursor, or `None` at the past-the-end position.
+    pub fn key(&self) -> Option<K> {
+        self.tree.get(self.index)
+    }
+
+    /// Step forward one key. Returns `false`, leaving the cursor where it
+    /// is, if it is already past the end.
+    // Not `Iterator::next`: a cursor moves both ways and reads the key
+    // separately, so stepping returns whether it moved.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> bool {
+        if self.index == self.tree.size() {
+            return false;
+        }
+        self.index += 1;
+        true
+    }
+
+    /// Step back one key. Returns `false`, leaving the cursor where it is,
+    /// if it is already at index 0.
+    pub fn prev(&mut self) -> bool {
+        if self.index == 0 {
+            return false;
+        }
+        self.index -= 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FastTree;
+
+    #[test]
+    fn steps_forward_past_the_end() {
+        let tree = FastTree::new(&[10, 20, 30]).unwrap();
+        let mut c = tree.cursor_at(15);
+        assert_eq!((c.index(), c.key()), (1, Some(20)));
+        assert!(c.next());
+        assert_eq!(c.key(), Some(30));
+        assert!(c.next());
+        assert_eq!((c.index(), c.key()), (3, None));
+        assert!(!c.next());
+        assert_eq!(c.index(), 3);
+        assert!(c.prev());
+        assert_eq!(c.key(), Some(30));
+    }
+
+    #[test]
+    fn steps_back_to_the_start() {
+        let tree = FastTree::new(&[10, 20, 30]).unwrap();
+        let mut c = tree.cursor_at(i32::MIN);
+        assert_eq!((c.index(), c.key()), (0, Some(10)));
+        assert!(!c.prev());
+        assert_eq!(c.index(), 0);
+
+        let mut c = tree.cursor_at(31);
+        assert_eq!((c.index(), c.key()), (3, None));
+        let mut back = Vec::new();
+        while c.prev() {
+            back.push(c.key().unwrap());
+        }
+        assert_eq!(back, [30, 20, 10]);
+    }
+
+    #[test]
+    fn merge_join_with_two_cursors() {
+        let a = FastTree::new(&[1, 3, 5, 7, 9]).unwrap();
+        let b = FastTree::new(&[2, 3, 4, 7, 8]).unwrap();
+        let (mut ca, mut cb) = (a.cursor_at(i32::MIN), b.cursor_at(i32::MIN));
+        let mut common = Vec::new();
+        while let (Some(x), Some(y)) = (ca.key(), cb.key()) {
+            if x < y {
+                ca.next();
+            } else if y < x {
+                cb.next();
+            } else {
+                common.push(x);
+                ca.next();
+                cb.next();
+            }
+        }
+        assert_eq!(common, [3, 7]);
+    }
+}
@@ -0,0 +1,136 @@
+//! Runtime choice of the `i32` search entry point, enabled by the
+//! `dispatch` feature.
+//!
+//! With the feature on, the crate links three builds of the C library: the
+//! plain one selected as usual (see [`isa_variant`](crate::isa_variant)),
+//! plus `libfast_avx2` (compiled with `-mavx2` and
+//! `FAST_SYMBOL_PREFIX=fast_avx2_`) and `libfast_avx512` (`-mavx512f` and
+//! `FAST_SYMBOL_PREFIX=fast_avx512_`), both built by CMake next to
+//! `libfast`. All three must come from the same sources, since a tree built
+//! by the plain library's `fast_create` is searched by whichever
+//! `fast_*_search` the CPU supports. The choice is made once, on the first
+//! search, and cached.
+
+use std::sync::OnceLock;
+
+use crate::ffi::{self, FastTreeOpaque};
+
+type SearchFn = unsafe extern "C" fn(*const FastTreeOpaque, i32) -> i64;
+type SearchBatchFn = unsafe extern "C" fn(*const FastTreeOpaque, *const i32, usize, *mut i64);
+
+/// The entry points chosen for this CPU, and the name of their build.
+struct Selected {
+    search: SearchFn,
+    search_batch: SearchBatchFn,
+    name: &'static str,
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[link(name = "fast_avx2")]
+extern "C" {
+    #[link_name = "fast_avx2_search"]
+    fn avx2_search(tree: *const FastTreeOpaque, key: i32) -> i64;
+    #[link_name = "fast_avx2_search_batch"]
+    fn avx2_search_batch(tree: *const FastTreeOpaque, keys: *const i32, n: usize, out: *mut i64);
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[link(name = "fast_avx512")]
+extern "C" {
+    #[link_name = "fast_avx512_search"]
+    fn avx512_search(tree: *const FastTreeOpaque, key: i32) -> i64;
+    #[link_name = "fast_avx512_search_batch"]
+    fn avx512_search_batch(tree: *const FastTreeOpaque, keys: *const i32, n: usize, out: *mut i64);
+}
+
+static SEARCH: OnceLock<Selected> = OnceLock::new();
+
+fn select() -> Selected {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::is_x86_feature_detected!("avx512f") {
+            return Selected {
+                search: avx512_search,
+                search_batch: avx512_search_batch,
+                name: "avx512",
+            };
+        }
+        if std::is_x86_feature_detected!("avx2") {
+            return Selected {
+                search: avx2_search,
+                search_batch: avx2_search_batch,
+                name: "avx2",
+            };
+        }
+    }
+    Selected {
+        search: ffi::fast_search,
+        search_batch: ffi::fast_search_batch,
+        name: crate::isa_variant(),
+    }
+}
+
+/// `fast_search` through the entry point chosen for this CPU.
+///
+/// # Safety
+/// `tree` must be a live tree from `fast_create`.
+pub(crate) unsafe fn fast_search_dispatch(tree: *const FastTreeOpaque, key: i32) -> i64 {
+    (SEARCH.get_or_init(select).search)(tree, key)
+}
+
+/// `fast_search_batch` through the entry point chosen for this CPU.
+///
+/// # Safety
+/// `tree` must be a live tree from `fast_create`, `keys` must point to `n`
+/// readable keys and `out` to `n` writable results.
+pub(crate) unsafe fn fast_search_batch_dispatch(
+    tree: *const FastTreeOpaque,
+    keys: *const i32,
+    n: usize,
+    out: *mut i64,
+) {
+    (SEARCH.get_or_init(select).search_batch)(tree, keys, n, out)
+}
+
+/// Which library build [`FastTree::search`](crate::FastTree::search) runs
+/// on this machine: `"avx512"` or `"avx2"` when the CPU supports them,
+/// otherwise the linked default reported by
+/// [`isa_variant`](crate::isa_variant).
+pub fn search_variant() -> &'static str {
+    SEARCH.get_or_init(select).name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fast_search_dispatch, search_variant};
+    use crate::query_gen::Lcg;
+    use crate::{ffi, FastTree};
+
+    #[test]
+    fn dispatched_search_matches_baseline() {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            let expected = if std::is_x86_feature_detected!("avx512f") {
+                "avx512"
+            } else if std::is_x86_feature_detected!("avx2") {
+                "avx2"
+            } else {
+                crate::isa_variant()
+            };
+            assert_eq!(search_variant(), expected);
+        }
+
+        let keys: Vec<i32> = (0..100_000).map(|i| i * 5 - 250_000).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        let ptr = tree.ptr.cast();
+        let mut rng = Lcg::new(5);
+        for _ in 0..20_000 {
+            let q = rng.next_i32_in(599_999) - 300_000;
+            let (dispatched, baseline) =
+                unsafe { (fast_search_dispatch(ptr, q), ffi::fast_search(ptr, q)) };
+            assert_eq!(dispatched, baseline, "query {q}");
+        }
+        assert_eq!(tree.search(i32::MIN), None);
+        assert_eq!(tree.search(i32::MAX), Some(keys.len() - 1));
+    }
+}
@@ -0,0 +1,60 @@
+//! Choosing among duplicate keys in a search.
+
+use crate::{FastKey, FastTree};
+
+/// Which occurrence of a duplicated key [`FastTree::search_with`] returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DupPolicy {
+    /// The first (lowest) index holding the matched key.
+    First,
+    /// The last (highest) index holding the matched key.
+    Last,
+    /// Whichever occurrence the C search lands on, at no extra cost: the
+    /// behaviour of [`FastTree::search`].
+    #[default]
+    Any,
+}
+
+impl<K: FastKey> FastTree<K> {
+    /// Like [`search`](FastTree::search), the index of the largest key <=
+    /// `key`, choosing among duplicates of that key by `policy`.
+    ///
+    /// `First` and `Last` cost one extra bound search each, resolving the
+    /// matched key `m` to [`lower_bound(m)`](FastTree::lower_bound) or
+    /// [`upper_bound(m)`](FastTree::upper_bound)` - 1`.
+    #[must_use]
+    pub fn search_with(&self, key: K, policy: DupPolicy) -> Option<usize> {
+        let index = self.search(key)?;
+        Some(match policy {
+            DupPolicy::Any => index,
+            DupPolicy::First => self.lower_bound(self.key_at(index)),
+            DupPolicy::Last => self.upper_bound(self.key_at(index)) - 1,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DupPolicy;
+    use crate::FastTree;
+
+    #[test]
+    fn policies_on_a_run() {
+        let tree = FastTree::new(&[1, 5, 5, 5, 5, 9]).unwrap();
+        for q in [5, 6, 8] {
+            assert_eq!(tree.search_with(q, DupPolicy::First), Some(1));
+            assert_eq!(tree.search_with(q, DupPolicy::Last), Some(4));
+            let any = tree.search_with(q, DupPolicy::Any).unwrap();
+            assert!((1..=4).contains(&any));
+            assert_eq!(Some(any), tree.search(q));
+        }
+        assert_eq!(tree.search_with(0, DupPolicy::First), None);
+        assert_eq!(tree.search_with(9, DupPolicy::First), Some(5));
+        assert_eq!(tree.search_with(i32::MAX, DupPolicy::Last), Some(5));
+        assert_eq!(DupPolicy::default(), DupPolicy::Any);
+
+        let all_equal = FastTree::new(&[3; 40]).unwrap();
+        assert_eq!(all_equal.search_with(3, DupPolicy::First), Some(0));
+        assert_eq!(all_equal.search_with(4, DupPolicy::Last), Some(39));
+    }
+}
@@ -0,0 +1,37 @@
+//! Error type for FAST tree construction.
+
+use core::fmt;
+
+/// Why a [`FastTree`](crate::FastTree) could not be built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FastTreeError {
+    /// The key slice was empty; the C library requires at least one key.
+    Empty,
+    /// The keys were not in non-decreasing order.
+    Unsorted,
+    /// The C library failed to allocate the tree.
+    AllocationFailed,
+    /// Serialized bytes did not start with a header this version can read.
+    InvalidHeader,
+    /// Serialized bytes were shorter or longer than their header declares.
+    Truncated,
+    /// Compressed bytes encoded a key delta that overflows the key type.
+    DeltaOverflow,
+}
+
+impl fmt::Display for FastTreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            FastTreeError::Empty => "cannot build a FAST tree from zero keys",
+            FastTreeError::Unsorted => "keys are not sorted in ascending order",
+            FastTreeError::AllocationFailed => "FAST tree allocation failed",
+            FastTreeError::InvalidHeader => "serialized FAST tree has an invalid header",
+            FastTreeError::Truncated => "serialized FAST tree length does not match its header",
+            FastTreeError::DeltaOverflow => "compressed FAST tree has a key delta out of range",
+        };
+        f.write_str(msg)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FastTreeError {}
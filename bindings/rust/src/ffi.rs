@@ -0,0 +1,117 @@
+//! Raw declarations of the C library's entry points.
+//!
+//! With the `avx2` or `avx512` feature the declarations bind to a library
+//! built with `FAST_SYMBOL_PREFIX=fast_avx2_` or `fast_avx512_` (see
+//! `fast.h`), so `fast_create` links against `fast_avx2_create`, and so on.
+//! Each entry point is declared once with its unprefixed suffix and
+//! `c_functions!` attaches the matching `link_name`. Cargo features are
+//! additive, so with both features on, `avx512` wins.
+
+use core::ffi::c_int;
+
+#[repr(C)]
+pub(crate) struct FastTreeOpaque {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub(crate) struct FastTree64Opaque {
+    _private: [u8; 0],
+}
+
+/// Declare `fn fast_name = "name" (args) -> ret;` items in one `extern`
+/// block, linked as `fast_name` or under the selected symbol prefix.
+macro_rules! c_functions {
+    ($($(#[$meta:meta])* fn $name:ident = $suffix:literal ($($arg:ident: $ty:ty),* $(,)?)
+        $(-> $ret:ty)?;)*) => {
+        extern "C" {
+            $(
+                $(#[$meta])*
+                #[cfg_attr(
+                    all(feature = "avx2", not(feature = "avx512")),
+                    link_name = concat!("fast_avx2_", $suffix)
+                )]
+                #[cfg_attr(feature = "avx512", link_name = concat!("fast_avx512_", $suffix))]
+                pub(crate) fn $name($($arg: $ty),*) $(-> $ret)?;
+            )*
+        }
+    };
+}
+
+c_functions! {
+    fn fast_create = "create" (keys: *const i32, n: usize) -> *mut FastTreeOpaque;
+    fn fast_destroy = "destroy" (tree: *mut FastTreeOpaque);
+    fn fast_search = "search" (tree: *const FastTreeOpaque, key: i32) -> i64;
+    fn fast_search_lower_bound = "search_lower_bound" (tree: *const FastTreeOpaque, key: i32)
+        -> i64;
+    fn fast_search_with_key = "search_with_key" (
+        tree: *const FastTreeOpaque,
+        key: i32,
+        out_key: *mut i32,
+    ) -> i64;
+    #[cfg(feature = "debug-trace")]
+    fn fast_search_trace = "search_trace" (
+        tree: *const FastTreeOpaque,
+        key: i32,
+        path_out: *mut usize,
+        path_cap: usize,
+    ) -> usize;
+    fn fast_search_batch = "search_batch" (
+        tree: *const FastTreeOpaque,
+        keys: *const i32,
+        n: usize,
+        out: *mut i64,
+    );
+    fn fast_size = "size" (tree: *const FastTreeOpaque) -> usize;
+    fn fast_key_at = "key_at" (tree: *const FastTreeOpaque, index: usize) -> i32;
+    fn fast_sorted_keys = "sorted_keys" (tree: *const FastTreeOpaque) -> *const i32;
+    fn fast_memory_bytes = "memory_bytes" (tree: *const FastTreeOpaque) -> usize;
+    fn fast_levels = "levels" (tree: *const FastTreeOpaque) -> c_int;
+    fn fast_blocking = "blocking" (
+        tree: *const FastTreeOpaque,
+        d_k: *mut c_int,
+        d_l: *mut c_int,
+        d_p: *mut c_int,
+    );
+
+    fn fast_create_i64 = "create_i64" (keys: *const i64, n: usize) -> *mut FastTree64Opaque;
+    fn fast_destroy_i64 = "destroy_i64" (tree: *mut FastTree64Opaque);
+    fn fast_search_i64 = "search_i64" (tree: *const FastTree64Opaque, key: i64) -> i64;
+    fn fast_search_with_key_i64 = "search_with_key_i64" (
+        tree: *const FastTree64Opaque,
+        key: i64,
+        out_key: *mut i64,
+    ) -> i64;
+    fn fast_search_lower_bound_i64 = "search_lower_bound_i64" (
+        tree: *const FastTree64Opaque,
+        key: i64,
+    ) -> i64;
+    fn fast_size_i64 = "size_i64" (tree: *const FastTree64Opaque) -> usize;
+    fn fast_key_at_i64 = "key_at_i64" (tree: *const FastTree64Opaque, index: usize) -> i64;
+    fn fast_sorted_keys_i64 = "sorted_keys_i64" (tree: *const FastTree64Opaque) -> *const i64;
+    fn fast_memory_bytes_i64 = "memory_bytes_i64" (tree: *const FastTree64Opaque) -> usize;
+}
+
+/// `fast_search`, or with the `dispatch` feature the build of it chosen for
+/// this CPU at run time.
+#[cfg(not(feature = "dispatch"))]
+#[inline]
+pub(crate) unsafe fn fast_search_dispatch(tree: *const FastTreeOpaque, key: i32) -> i64 {
+    fast_search(tree, key)
+}
+
+/// `fast_search_batch`, dispatched like
+/// [`fast_search_dispatch`].
+#[cfg(not(feature = "dispatch"))]
+#[inline]
+pub(crate) unsafe fn fast_search_batch_dispatch(
+    tree: *const FastTreeOpaque,
+    keys: *const i32,
+    n: usize,
+    out: *mut i64,
+) {
+    fast_search_batch(tree, keys, n, out)
+}
+
+#[cfg(feature = "dispatch")]
+pub(crate) use crate::dispatch::{fast_search_batch_dispatch, fast_search_dispatch};
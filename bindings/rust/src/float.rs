@@ -0,0 +1,138 @@
+//! `f32` keys stored in an `i32` tree through an order-preserving encoding.
+
+use alloc::vec::Vec;
+
+use crate::FastTree;
+
+/// Map `x` to an `i32` whose signed order matches [`f32::total_cmp`].
+///
+/// The usual sign-flip trick: non-negative floats keep their bit pattern,
+/// and negative floats have their magnitude bits inverted so larger
+/// magnitudes sort lower. The map is a bijection, so
+/// [`decode_f32`] recovers `x` bit for bit. Under this order `-0.0` sorts
+/// just below `0.0`; NaNs with the sign bit clear sort above infinity, and
+/// those with it set sort below negative infinity.
+pub fn encode_f32(x: f32) -> i32 {
+    let bits = x.to_bits() as i32;
+    if bits < 0 { bits ^ i32::MAX } else { bits }
+}
+
+/// The inverse of [`encode_f32`].
+pub fn decode_f32(k: i32) -> f32 {
+    let bits = if k < 0 { k ^ i32::MAX } else { k };
+    f32::from_bits(bits as u32)
+}
+
+/// A FAST tree over `f32` keys, encoded with [`encode_f32`] into an `i32`
+/// tree so searches run on the integer SIMD path.
+///
+/// Keys are ordered by [`f32::total_cmp`], so `-0.0` and `0.0` are distinct
+/// keys with `-0.0` first. NaN is not a key: construction rejects it, and a
+/// NaN query finds nothing: `search` and `lower_bound` return `None`, and
+/// `contains` is false.
+pub struct FastTreeF32 {
+    tree: FastTree,
+}
+
+impl FastTreeF32 {
+    /// Build a tree from keys sorted by [`f32::total_cmp`] (for non-NaN
+    /// values, ordinary ascending order with `-0.0` before `0.0`).
+    ///
+    /// Returns `None` if `keys` is empty, unsorted, or contains a NaN.
+    pub fn new(keys: &[f32]) -> Option<Self> {
+        if keys.iter().any(|k| k.is_nan()) {
+            return None;
+        }
+        // The encoding preserves total_cmp order, so checking the encoded
+        // keys checks the floats.
+        let encoded: Vec<i32> = keys.iter().map(|&k| encode_f32(k)).collect();
+        FastTree::new(&encoded).map(|tree| FastTreeF32 { tree })
+    }
+
+    /// Index of the largest key <= `key`, or `None` if every key is greater
+    /// or `key` is NaN.
+    pub fn search(&self, key: f32) -> Option<usize> {
+        if key.is_nan() {
+            return None;
+        }
+        self.tree.search(encode_f32(key))
+    }
+
+    /// Index of the first key >= `key` (may equal `size()`), or `None` if
+    /// `key` is NaN.
+    pub fn lower_bound(&self, key: f32) -> Option<usize> {
+        if key.is_nan() {
+            return None;
+        }
+        Some(self.tree.lower_bound(encode_f32(key)))
+    }
+
+    /// Whether `key` is stored; always false for NaN.
+    pub fn contains(&self, key: f32) -> bool {
+        !key.is_nan() && self.tree.contains(encode_f32(key))
+    }
+
+    /// The key at a sorted index, or `None` if `index >= size()`.
+    pub fn get(&self, index: usize) -> Option<f32> {
+        self.tree.get(index).map(decode_f32)
+    }
+
+    /// Number of keys.
+    pub fn size(&self) -> usize {
+        self.tree.size()
+    }
+
+    /// The underlying tree of encoded keys.
+    pub fn tree(&self) -> &FastTree {
+        &self.tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoding_preserves_order() {
+        let values = [
+            f32::NEG_INFINITY, -1e30, -2.5, -1.0, -f32::MIN_POSITIVE, -0.0, 0.0,
+            f32::MIN_POSITIVE, 1.0, 2.5, 1e30, f32::INFINITY,
+        ];
+        for w in values.windows(2) {
+            assert!(encode_f32(w[0]) < encode_f32(w[1]), "{} vs {}", w[0], w[1]);
+        }
+        for &v in &values {
+            assert_eq!(decode_f32(encode_f32(v)).to_bits(), v.to_bits());
+        }
+        assert!(encode_f32(f32::NAN) > encode_f32(f32::INFINITY));
+        assert!(encode_f32(-f32::NAN) < encode_f32(f32::NEG_INFINITY));
+    }
+
+    #[test]
+    fn searches_float_keys() {
+        let tree = FastTreeF32::new(&[-3.5, -1.0, -0.0, 0.0, 2.25, 10.0]).unwrap();
+        assert_eq!(tree.size(), 6);
+        assert_eq!(tree.search(-4.0), None);
+        assert_eq!(tree.search(-1.0), Some(1));
+        assert_eq!(tree.search(-0.5), Some(1));
+        assert_eq!(tree.search(-0.0), Some(2));
+        assert_eq!(tree.search(1.0), Some(3));
+        assert_eq!(tree.search(f32::INFINITY), Some(5));
+        assert_eq!(tree.lower_bound(2.0), Some(4));
+        assert_eq!(tree.lower_bound(11.0), Some(6));
+        assert!(tree.contains(2.25) && !tree.contains(2.5));
+        assert_eq!(tree.get(0), Some(-3.5));
+        assert_eq!(tree.get(6), None);
+    }
+
+    #[test]
+    fn nan_policy() {
+        assert!(FastTreeF32::new(&[1.0, f32::NAN]).is_none());
+        assert!(FastTreeF32::new(&[2.0, 1.0]).is_none());
+        assert!(FastTreeF32::new(&[]).is_none());
+        let tree = FastTreeF32::new(&[1.0, 2.0]).unwrap();
+        assert_eq!(tree.search(f32::NAN), None);
+        assert_eq!(tree.lower_bound(f32::NAN), None);
+        assert!(!tree.contains(f32::NAN));
+    }
+}
@@ -0,0 +1,77 @@
+//! Building through a huge-page staging buffer, enabled by the `hugepages`
+//! feature on Linux.
+
+use memmap2::{Advice, MmapMut};
+
+use crate::{check_len, is_sorted, FastKey, FastTree, FastTreeError};
+
+/// Size of an x86-64 transparent huge page; the staging buffer is rounded
+/// up to a multiple so the kernel can back all of it with huge pages.
+const HUGE_PAGE: usize = 2 << 20;
+
+impl<K: FastKey> FastTree<K> {
+    /// Like [`try_new`](FastTree::try_new), staging the keys in an
+    /// anonymous mapping advised with `MADV_HUGEPAGE` before handing them to
+    /// the C constructor.
+    ///
+    /// Meant for multi-gigabyte key sets, where the build's pass over the
+    /// input incurs a TLB miss every 4 KiB page; with transparent huge pages
+    /// one entry covers 2 MiB. The C library copies the keys and allocates
+    /// its search layout itself (page-aligned, with ordinary pages), so the
+    /// huge pages speed up construction only, not later searches, and the
+    /// staging buffer is unmapped before this returns.
+    ///
+    /// Huge pages are a request, not a guarantee: the kernel needs
+    /// `CONFIG_TRANSPARENT_HUGEPAGE` and
+    /// `/sys/kernel/mm/transparent_hugepage/enabled` set to `madvise` or
+    /// `always`. When the advice is refused, or the mapping itself fails,
+    /// the tree is built the ordinary way; the result is identical in
+    /// every case.
+    pub fn reserve_and_build(keys: &[K]) -> Result<Self, FastTreeError> {
+        check_len(keys.len())?;
+        if !is_sorted(keys) {
+            return Err(FastTreeError::Unsorted);
+        }
+        let bytes = core::mem::size_of_val(keys);
+        let Ok(mut map) = MmapMut::map_anon(bytes.div_ceil(HUGE_PAGE).max(1) * HUGE_PAGE) else {
+            return Self::build(keys);
+        };
+        // Refusal only means ordinary pages; the buffer works either way.
+        let _ = map.advise(Advice::HugePage);
+        // SAFETY: the mapping is page-aligned, so aligned for `K`, and at
+        // least `bytes` long; it outlives the slice, which `build` only
+        // reads while the C library copies it.
+        let staged = unsafe {
+            let dst = core::slice::from_raw_parts_mut(map.as_mut_ptr().cast::<K>(), keys.len());
+            dst.copy_from_slice(keys);
+            &*dst
+        };
+        Self::build(staged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FastTree, FastTree64, FastTreeError};
+
+    #[test]
+    fn matches_normal_constructor() {
+        let keys: Vec<i32> = (0..700_000).map(|i| i * 3 - 1_000_000).collect();
+        let staged = FastTree::reserve_and_build(&keys).unwrap();
+        let plain = FastTree::new(&keys).unwrap();
+        assert_eq!(staged, plain);
+        assert_eq!(staged.memory_usage(), plain.memory_usage());
+        for q in [i32::MIN, -1_000_001, 0, 5, 1_099_997, i32::MAX] {
+            assert_eq!(staged.search(q), plain.search(q), "query {q}");
+        }
+
+        let small = FastTree64::reserve_and_build(&[-1, 1 << 40]).unwrap();
+        assert_eq!(small.sorted_keys(), [-1, 1 << 40]);
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert_eq!(FastTree::<i32>::reserve_and_build(&[]).unwrap_err(), FastTreeError::Empty);
+        assert_eq!(FastTree::reserve_and_build(&[2, 1]).unwrap_err(), FastTreeError::Unsorted);
+    }
+}
@@ -0,0 +1,241 @@
+//! Iterators over the keys of a [`FastTree`].
+
+use core::iter::FusedIterator;
+
+use crate::{FastKey, FastTree};
+
+/// Iterator over the keys of a [`FastTree`] in sorted order.
+///
+/// Created by [`FastTree::iter`], [`FastTree::keys_from`] and
+/// [`FastTree::keys_until`].
+pub struct FastTreeIter<'a, K: FastKey = i32> {
+    tree: &'a FastTree<K>,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, K: FastKey> FastTreeIter<'a, K> {
+    pub(crate) fn new(tree: &'a FastTree<K>, start: usize, end: usize) -> Self {
+        FastTreeIter {
+            tree,
+            front: start,
+            back: end,
+        }
+    }
+}
+
+impl<K: FastKey> Iterator for FastTreeIter<'_, K> {
+    type Item = K;
+
+    fn next(&mut self) -> Option<K> {
+        if self.front == self.back {
+            return None;
+        }
+        let key = self.tree.key_at(self.front);
+        self.front += 1;
+        Some(key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.back - self.front;
+        (n, Some(n))
+    }
+}
+
+impl<K: FastKey> DoubleEndedIterator for FastTreeIter<'_, K> {
+    fn next_back(&mut self) -> Option<K> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.tree.key_at(self.back))
+    }
+}
+
+impl<K: FastKey> ExactSizeIterator for FastTreeIter<'_, K> {}
+
+impl<K: FastKey> FusedIterator for FastTreeIter<'_, K> {}
+
+/// Iterates over the keys in sorted order, like [`FastTree::iter`].
+///
+/// ```no_run
+/// use fast_tree::FastTree;
+/// let tree = FastTree::new(&[2, 4, 8]).unwrap();
+/// let mut seen = Vec::new();
+/// for k in &tree {
+///     seen.push(k);
+/// }
+/// assert_eq!(seen, [2, 4, 8]);
+/// ```
+impl<'a, K: FastKey> IntoIterator for &'a FastTree<K> {
+    type Item = K;
+    type IntoIter = FastTreeIter<'a, K>;
+
+    fn into_iter(self) -> FastTreeIter<'a, K> {
+        self.iter()
+    }
+}
+
+/// Iterator over `(index, key)` pairs of a [`FastTree`] within key bounds.
+///
+/// Created by [`FastTree::range`] and [`FastTree::entries`].
+pub struct FastTreeRange<'a, K: FastKey = i32> {
+    tree: &'a FastTree<K>,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, K: FastKey> FastTreeRange<'a, K> {
+    pub(crate) fn new(tree: &'a FastTree<K>, start: usize, end: usize) -> Self {
+        FastTreeRange {
+            tree,
+            front: start,
+            back: end,
+        }
+    }
+}
+
+impl<K: FastKey> Iterator for FastTreeRange<'_, K> {
+    type Item = (usize, K);
+
+    fn next(&mut self) -> Option<(usize, K)> {
+        if self.front == self.back {
+            return None;
+        }
+        let index = self.front;
+        self.front += 1;
+        Some((index, self.tree.key_at(index)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.back - self.front;
+        (n, Some(n))
+    }
+}
+
+impl<K: FastKey> DoubleEndedIterator for FastTreeRange<'_, K> {
+    fn next_back(&mut self) -> Option<(usize, K)> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some((self.back, self.tree.key_at(self.back)))
+    }
+}
+
+impl<K: FastKey> ExactSizeIterator for FastTreeRange<'_, K> {}
+
+impl<K: FastKey> FusedIterator for FastTreeRange<'_, K> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::FastTree;
+
+    #[test]
+    fn iter_forward_and_backward() {
+        let keys = [1, 3, 5, 7, 9];
+        let tree = FastTree::new(&keys).unwrap();
+        assert_eq!(tree.iter().collect::<Vec<_>>(), keys);
+        assert_eq!(tree.iter().rev().collect::<Vec<_>>(), [9, 7, 5, 3, 1]);
+    }
+
+    #[test]
+    fn iter_exact_len() {
+        let tree = FastTree::new(&[1, 2, 3, 4]).unwrap();
+        let mut it = tree.iter();
+        assert_eq!(it.len(), 4);
+        it.next();
+        it.next_back();
+        assert_eq!(it.len(), 2);
+        assert_eq!(it.size_hint(), (2, Some(2)));
+    }
+
+    #[test]
+    fn iter_meets_in_middle() {
+        let tree = FastTree::new(&[1, 2, 3]).unwrap();
+        let mut it = tree.iter();
+        assert_eq!(it.next(), Some(1));
+        assert_eq!(it.next_back(), Some(3));
+        assert_eq!(it.next(), Some(2));
+        assert_eq!(it.next_back(), None);
+        assert_eq!(it.next(), None);
+        assert_eq!(it.len(), 0);
+    }
+
+    #[test]
+    fn keys_from_and_until() {
+        let tree = FastTree::new(&[10, 20, 20, 30, 40]).unwrap();
+        for q in [5, 10, 15, 20, 25, 40, 41] {
+            let lb = tree.lower_bound(q);
+            let from: Vec<_> = tree.keys_from(q).collect();
+            let until: Vec<_> = tree.keys_until(q).collect();
+            assert_eq!(from.len(), tree.size() - lb);
+            assert_eq!(until.len(), lb);
+            assert_eq!(from.first().copied(), tree.get(lb));
+            assert_eq!(until.last().copied(), lb.checked_sub(1).and_then(|i| tree.get(i)));
+            assert_eq!([until, from].concat(), tree.to_vec());
+        }
+        assert_eq!(tree.keys_from(20).collect::<Vec<_>>(), [20, 20, 30, 40]);
+        assert_eq!(tree.keys_until(30).rev().collect::<Vec<_>>(), [20, 20, 10]);
+    }
+
+    #[test]
+    fn entries_are_contiguous_from_zero() {
+        let tree = FastTree::new(&[-4, 0, 0, 9, 12, 40]).unwrap();
+        let entries = tree.entries();
+        assert_eq!(entries.len(), tree.size());
+        for (expected, (i, k)) in entries.enumerate() {
+            assert_eq!((i, k), (expected, tree.key_at(expected)));
+        }
+        let back: Vec<_> = tree.entries().rev().map(|(i, _)| i).collect();
+        assert_eq!(back, [5, 4, 3, 2, 1, 0]);
+    }
+
+    fn range_keys(tree: &FastTree, r: impl std::ops::RangeBounds<i32>) -> Vec<i32> {
+        tree.range(r).map(|(_, k)| k).collect()
+    }
+
+    #[test]
+    fn for_loop_over_reference() {
+        let tree = FastTree::from_unsorted(&[9, 3, 6, 3]).unwrap();
+        let mut seen = Vec::new();
+        for k in &tree {
+            seen.push(k);
+        }
+        assert_eq!(seen, [3, 3, 6, 9]);
+        assert_eq!((&tree).into_iter().next_back(), Some(9));
+    }
+
+    #[test]
+    fn range_bounds() {
+        let tree = FastTree::new(&[10, 20, 30, 40, 50]).unwrap();
+        assert_eq!(range_keys(&tree, 20..40), [20, 30]);
+        assert_eq!(range_keys(&tree, 20..=40), [20, 30, 40]);
+        assert_eq!(range_keys(&tree, 15..45), [20, 30, 40]);
+        assert_eq!(range_keys(&tree, ..30), [10, 20]);
+        assert_eq!(range_keys(&tree, 30..), [30, 40, 50]);
+        assert_eq!(range_keys(&tree, ..), [10, 20, 30, 40, 50]);
+        use std::ops::Bound::{Excluded, Included};
+        assert_eq!(range_keys(&tree, (Excluded(20), Included(40))), [30, 40]);
+        assert_eq!(range_keys(&tree, (Excluded(20), Excluded(40))), [30]);
+    }
+
+    #[test]
+    fn range_yields_indices() {
+        let tree = FastTree::new(&[1, 2, 2, 2, 5]).unwrap();
+        let entries: Vec<_> = tree.range(2..=2).collect();
+        assert_eq!(entries, [(1, 2), (2, 2), (3, 2)]);
+        assert_eq!(tree.range(2..).next_back(), Some((4, 5)));
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn range_empty() {
+        let tree = FastTree::new(&[10, 20, 30]).unwrap();
+        assert_eq!(tree.range(21..30).len(), 0);
+        assert_eq!(tree.range(30..10).len(), 0);
+        assert_eq!(tree.range(20..20).len(), 0);
+        assert_eq!(tree.range(100..).len(), 0);
+        assert_eq!(tree.range(..10).len(), 0);
+    }
+}
@@ -0,0 +1,325 @@
+//! Key types with a FAST implementation in the C library.
+
+use alloc::vec::Vec;
+use core::ffi::c_void;
+use core::fmt::Debug;
+use core::hash::Hash;
+
+use crate::ffi;
+
+mod sealed {
+    use alloc::vec::Vec;
+    use core::ffi::c_void;
+
+    /// Seals [`FastKey`](super::FastKey), and carries the arithmetic and
+    /// encoding helpers the crate needs for every key type without making
+    /// them part of the public trait.
+    pub trait Sealed: Sized {
+        /// `|self - other|`, computed without overflow.
+        fn distance(self, other: Self) -> u64;
+
+        /// The key `delta` above `self`, or `None` past the type's maximum:
+        /// the inverse of [`distance`](Sealed::distance) for `other >= self`.
+        fn checked_add_distance(self, delta: u64) -> Option<Self>;
+
+        /// Size of one key in bytes, as stored by
+        /// [`to_bytes`](crate::FastTree::to_bytes).
+        const WIDTH: usize;
+
+        /// Append the little-endian encoding of `self` to `out`.
+        fn write_le(self, out: &mut Vec<u8>);
+
+        /// Decode a key from little-endian bytes.
+        ///
+        /// # Panics
+        /// Panics unless `bytes` is exactly [`WIDTH`](Sealed::WIDTH) long.
+        fn read_le(bytes: &[u8]) -> Self;
+
+        /// Raw search results for every key in `keys`, as `search` gives
+        /// them, in one call where the C library has a batch entry point.
+        ///
+        /// # Safety
+        /// `tree` must be a live tree of this key type, as for
+        /// [`FastKey`](super::FastKey)'s methods, and `out` must be exactly
+        /// as long as `keys`.
+        unsafe fn search_batch(tree: *const c_void, keys: &[Self], out: &mut [i64]);
+    }
+}
+
+/// A key type that [`FastTree`](crate::FastTree) can be built over.
+///
+/// Each implementation forwards to one family of C entry points: the plain
+/// `fast_*` functions for `i32` and the `_i64` variants for `i64`. The tree
+/// handle is passed as an untyped pointer so one generic `FastTree` serves
+/// every width. The trait is sealed; adding a width (say `u32`) means adding
+/// its C functions and an impl here, with no change to `FastTree`'s API.
+///
+/// For all of the unsafe methods, every `tree` argument must be a live,
+/// non-null pointer returned by [`create`](FastKey::create) for the same
+/// key type.
+pub trait FastKey: Copy + Ord + Hash + Debug + sealed::Sealed {
+    /// Build a tree from `n >= 1` sorted keys; null on failure.
+    ///
+    /// # Safety
+    /// `keys` must point to `n` readable keys.
+    unsafe fn create(keys: *const Self, n: usize) -> *mut c_void;
+
+    /// Free a tree.
+    ///
+    /// # Safety
+    /// See the trait documentation; `tree` must not be used afterwards.
+    unsafe fn destroy(tree: *mut c_void);
+
+    /// Index of the largest key <= `key`, or -1.
+    ///
+    /// # Safety
+    /// See the trait documentation.
+    unsafe fn search(tree: *const c_void, key: Self) -> i64;
+
+    /// Like [`search`](FastKey::search), also storing the matched key in
+    /// `*out_key` on a hit.
+    ///
+    /// # Safety
+    /// See the trait documentation; `out_key` must be writable.
+    unsafe fn search_with_key(tree: *const c_void, key: Self, out_key: *mut Self) -> i64;
+
+    /// Index of the first key >= `key`, or the size if there is none.
+    ///
+    /// # Safety
+    /// See the trait documentation.
+    unsafe fn lower_bound(tree: *const c_void, key: Self) -> i64;
+
+    /// Number of keys.
+    ///
+    /// # Safety
+    /// See the trait documentation.
+    unsafe fn size(tree: *const c_void) -> usize;
+
+    /// Key at a sorted index.
+    ///
+    /// # Safety
+    /// See the trait documentation.
+    unsafe fn key_at(tree: *const c_void, index: usize) -> Self;
+
+    /// Pointer to the tree's sorted key array, `size` entries long.
+    ///
+    /// # Safety
+    /// See the trait documentation.
+    unsafe fn sorted_keys(tree: *const c_void) -> *const Self;
+
+    /// Heap bytes owned by the tree.
+    ///
+    /// # Safety
+    /// See the trait documentation.
+    unsafe fn memory_bytes(tree: *const c_void) -> usize;
+
+    /// The next larger key value, or `None` at the type's maximum.
+    fn checked_next(self) -> Option<Self>;
+}
+
+macro_rules! impl_fast_key {
+    ($key:ty, $opaque:ty, $create:ident, $destroy:ident, $search:ident, $search_batch:path,
+     $search_with_key:ident, $lower_bound:ident, $size:ident, $key_at:ident, $sorted_keys:ident,
+     $memory_bytes:ident) => {
+        impl FastKey for $key {
+            unsafe fn create(keys: *const Self, n: usize) -> *mut c_void {
+                ffi::$create(keys, n).cast()
+            }
+
+            unsafe fn destroy(tree: *mut c_void) {
+                ffi::$destroy(tree.cast::<$opaque>())
+            }
+
+            unsafe fn search(tree: *const c_void, key: Self) -> i64 {
+                ffi::$search(tree.cast::<$opaque>(), key)
+            }
+
+            unsafe fn search_with_key(tree: *const c_void, key: Self, out_key: *mut Self) -> i64 {
+                ffi::$search_with_key(tree.cast::<$opaque>(), key, out_key)
+            }
+
+            unsafe fn lower_bound(tree: *const c_void, key: Self) -> i64 {
+                ffi::$lower_bound(tree.cast::<$opaque>(), key)
+            }
+
+            unsafe fn size(tree: *const c_void) -> usize {
+                ffi::$size(tree.cast::<$opaque>())
+            }
+
+            unsafe fn key_at(tree: *const c_void, index: usize) -> Self {
+                ffi::$key_at(tree.cast::<$opaque>(), index)
+            }
+
+            unsafe fn sorted_keys(tree: *const c_void) -> *const Self {
+                ffi::$sorted_keys(tree.cast::<$opaque>())
+            }
+
+            unsafe fn memory_bytes(tree: *const c_void) -> usize {
+                ffi::$memory_bytes(tree.cast::<$opaque>())
+            }
+
+            fn checked_next(self) -> Option<Self> {
+                self.checked_add(1)
+            }
+        }
+
+        impl sealed::Sealed for $key {
+            fn distance(self, other: Self) -> u64 {
+                // abs_diff widens to the unsigned type, so i32::MIN..i32::MAX
+                // (or the i64 extremes) cannot overflow.
+                self.abs_diff(other) as u64
+            }
+
+            fn checked_add_distance(self, delta: u64) -> Option<Self> {
+                <$key>::try_from(i128::from(self) + i128::from(delta)).ok()
+            }
+
+            const WIDTH: usize = core::mem::size_of::<$key>();
+
+            fn write_le(self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn read_le(bytes: &[u8]) -> Self {
+                let mut buf = [0u8; core::mem::size_of::<$key>()];
+                buf.copy_from_slice(bytes);
+                <$key>::from_le_bytes(buf)
+            }
+
+            unsafe fn search_batch(tree: *const c_void, keys: &[Self], out: &mut [i64]) {
+                debug_assert_eq!(keys.len(), out.len());
+                $search_batch(tree.cast::<$opaque>(), keys.as_ptr(), keys.len(), out.as_mut_ptr())
+            }
+        }
+    };
+}
+
+impl_fast_key!(
+    i32, ffi::FastTreeOpaque,
+    fast_create, fast_destroy, fast_search_dispatch, ffi::fast_search_batch_dispatch,
+    fast_search_with_key,
+    fast_search_lower_bound,
+    fast_size, fast_key_at, fast_sorted_keys, fast_memory_bytes
+);
+
+impl_fast_key!(
+    i64, ffi::FastTree64Opaque,
+    fast_create_i64, fast_destroy_i64, fast_search_i64, search_batch_i64,
+    fast_search_with_key_i64,
+    fast_search_lower_bound_i64,
+    fast_size_i64, fast_key_at_i64, fast_sorted_keys_i64, fast_memory_bytes_i64
+);
+
+/// The C library has no batch search for 64-bit keys, so `i64` batches
+/// loop over `fast_search_i64`.
+unsafe fn search_batch_i64(
+    tree: *const ffi::FastTree64Opaque,
+    keys: *const i64,
+    n: usize,
+    out: *mut i64,
+) {
+    for i in 0..n {
+        *out.add(i) = ffi::fast_search_i64(tree, *keys.add(i));
+    }
+}
+
+/// A key type whose "C library" is a Rust struct returning scripted
+/// results, so tests can feed `FastTree` answers the real library never
+/// gives.
+#[cfg(test)]
+pub(crate) mod stub {
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+    use core::ffi::c_void;
+
+    use super::sealed::Sealed;
+    use super::FastKey;
+
+    #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+    pub(crate) struct StubKey(pub(crate) i32);
+
+    /// The stub tree: real keys for the accessors, fixed search results.
+    pub(crate) struct StubTree {
+        pub(crate) keys: Vec<StubKey>,
+        pub(crate) search: i64,
+        pub(crate) lower_bound: i64,
+    }
+
+    impl StubTree {
+        /// A pointer for [`FastTree::from_raw`](crate::FastTree::from_raw),
+        /// freed by the tree's `Drop`.
+        pub(crate) fn into_raw(self) -> *mut c_void {
+            Box::into_raw(Box::new(self)).cast()
+        }
+    }
+
+    unsafe fn stub<'a>(tree: *const c_void) -> &'a StubTree {
+        &*tree.cast::<StubTree>()
+    }
+
+    impl FastKey for StubKey {
+        unsafe fn create(_keys: *const Self, _n: usize) -> *mut c_void {
+            core::ptr::null_mut()
+        }
+
+        unsafe fn destroy(tree: *mut c_void) {
+            drop(Box::from_raw(tree.cast::<StubTree>()));
+        }
+
+        unsafe fn search(tree: *const c_void, _key: Self) -> i64 {
+            stub(tree).search
+        }
+
+        unsafe fn search_with_key(tree: *const c_void, _key: Self, _out_key: *mut Self) -> i64 {
+            stub(tree).search
+        }
+
+        unsafe fn lower_bound(tree: *const c_void, _key: Self) -> i64 {
+            stub(tree).lower_bound
+        }
+
+        unsafe fn size(tree: *const c_void) -> usize {
+            if tree.is_null() { 0 } else { stub(tree).keys.len() }
+        }
+
+        unsafe fn key_at(tree: *const c_void, index: usize) -> Self {
+            stub(tree).keys[index]
+        }
+
+        unsafe fn sorted_keys(tree: *const c_void) -> *const Self {
+            stub(tree).keys.as_ptr()
+        }
+
+        unsafe fn memory_bytes(_tree: *const c_void) -> usize {
+            0
+        }
+
+        fn checked_next(self) -> Option<Self> {
+            self.0.checked_next().map(StubKey)
+        }
+    }
+
+    impl Sealed for StubKey {
+        fn distance(self, other: Self) -> u64 {
+            self.0.distance(other.0)
+        }
+
+        fn checked_add_distance(self, delta: u64) -> Option<Self> {
+            self.0.checked_add_distance(delta).map(StubKey)
+        }
+
+        const WIDTH: usize = 4;
+
+        fn write_le(self, out: &mut Vec<u8>) {
+            self.0.write_le(out);
+        }
+
+        fn read_le(bytes: &[u8]) -> Self {
+            StubKey(i32::read_le(bytes))
+        }
+
+        unsafe fn search_batch(tree: *const c_void, _keys: &[Self], out: &mut [i64]) {
+            out.fill(stub(tree).search);
+        }
+    }
+}
@@ -8,71 +8,2620 @@
 //! assert_eq!(tree.search(5), Some(2));
 //! assert_eq!(tree.search(0), None);
 //! ```
+//!
+//! # `no_std`
+//!
+//! The crate needs an allocator but not `std`. Building with
+//! `default-features = false` drops the `std` feature and makes it
+//! `#![no_std]` on top of `alloc`; [`FastTreeError`] then implements only
+//! `Display`, not `std::error::Error`, and the `memmap2`, `rayon` and
+//! `tokio` features (which enable `std`) are unavailable. `tests/no_std`
+//! is a `#![no_std]` crate that uses the binding this way; building it for
+//! a platform with no `std` checks that none has crept back in:
+//! `cargo build --manifest-path tests/no_std/Cargo.toml --target x86_64-unknown-none`.
+//!
+//! The `hugepages` feature (which enables `memmap2`) adds
+//! `FastTree::reserve_and_build` on Linux; elsewhere it has no effect.
+//!
+//! # Linking
+//!
+//! The crate declares `links = "fast"` and expects the C library to be
+//! built and linked by the surrounding build (a `build.rs`, or
+//! `RUSTFLAGS` pointing at `libfast`). By default it binds the plain
+//! `fast_*` symbols. The `avx2` and `avx512` features instead bind a
+//! library compiled with `-DFAST_SYMBOL_PREFIX=fast_avx2_` or
+//! `fast_avx512_` (the CMake cache variable of the same name does this),
+//! so `fast_create` resolves to `fast_avx2_create` and so on; the library
+//! linked must have been built with the matching prefix. If both features
+//! are on, `avx512` takes precedence. [`isa_variant`] reports which build
+//! the crate was compiled against.
+//!
+//! The `dispatch` feature (which enables `std`) picks at run time instead:
+//! it also links the `fast_avx2_` and `fast_avx512_` builds and routes
+//! `i32` searches to the best one the CPU supports, detected once with
+//! `is_x86_feature_detected!`. `search_variant` reports the choice. The
+//! CMake build produces those two as `libfast_avx2.a` and
+//! `libfast_avx512.a`, and the crate names them itself, so only the search
+//! path is needed:
+//!
+//! ```text
+//! cmake -B build && cmake --build build
+//! RUSTFLAGS="-L native=$PWD/build -l static=fast" cargo test --features dispatch
+//! ```
+//!
+//! The C library has no AVX2 or AVX-512 search yet: all of these builds
+//! run the same SSE2 code, and the prefixed ones differ only in what the
+//! compiler makes of it with the wider instruction sets enabled.
 
-#[repr(C)]
-struct FastTreeOpaque {
-    _private: [u8; 0],
-}
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
-extern "C" {
-    fn fast_create(keys: *const i32, n: usize) -> *mut FastTreeOpaque;
-    fn fast_destroy(tree: *mut FastTreeOpaque);
-    fn fast_search(tree: *const FastTreeOpaque, key: i32) -> i64;
-    fn fast_search_lower_bound(tree: *const FastTreeOpaque, key: i32) -> i64;
-    fn fast_size(tree: *const FastTreeOpaque) -> usize;
-    fn fast_key_at(tree: *const FastTreeOpaque, index: usize) -> i32;
-}
+extern crate alloc;
+
+#[cfg(feature = "bench-helpers")]
+pub mod bench;
+mod bloom;
+mod buf;
+mod builder;
+mod bytes;
+mod compressed;
+mod cursor;
+#[cfg(feature = "dispatch")]
+mod dispatch;
+mod dup;
+mod error;
+mod ffi;
+mod float;
+#[cfg(all(feature = "hugepages", target_os = "linux"))]
+mod hugepage;
+mod iter;
+mod key;
+mod locate;
+mod map;
+#[cfg(feature = "memmap2")]
+mod mmap;
+#[cfg(feature = "rayon")]
+mod par;
+mod permuted;
+#[cfg(any(feature = "std", test))]
+pub mod query_gen;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod stats;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+mod tiny;
+#[cfg(feature = "tokio")]
+mod tokio_impl;
+
+pub use bloom::FastTreeBloom;
+pub use buf::FastTreeBuf;
+pub use builder::FastTreeBuilder;
+pub use cursor::Cursor;
+#[cfg(feature = "dispatch")]
+pub use dispatch::search_variant;
+pub use dup::DupPolicy;
+pub use error::FastTreeError;
+pub use float::{decode_f32, encode_f32, FastTreeF32};
+pub use iter::{FastTreeIter, FastTreeRange};
+pub use key::FastKey;
+pub use locate::Located;
+pub use map::FastMap;
+#[cfg(feature = "memmap2")]
+pub use mmap::FastTreeMmap;
+pub use permuted::FastTreePermuted;
+pub use stats::{FastTreeStats, QueryStats};
+
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ffi::c_void;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::{Bound, Index, Range, RangeBounds};
+
+use tiny::TinyKeys;
 
 /// A FAST search tree wrapping the C library.
-pub struct FastTree {
-    ptr: *mut FastTreeOpaque,
+///
+/// Generic over the key type; `FastTree` on its own means `FastTree<i32>`.
+/// See [`FastKey`] for the supported widths.
+///
+/// Trees of up to 16 keys also keep a boxed copy of their keys, and
+/// [`search`](FastTree::search) and [`lower_bound`](FastTree::lower_bound)
+/// answer with a fixed-width scan of it in Rust, which beats a call into
+/// the C library at that size (`bench::run_tiny_crossover` measures the
+/// two). The C tree is built all the same, so every other method, and
+/// [`into_raw`](FastTree::into_raw), behaves identically; larger trees pay
+/// only for an empty `Option<Box<_>>`.
+///
+/// The per-query methods are `#[inline]` and the query results
+/// `#[must_use]`. The generic methods are instantiated in the caller's
+/// crate regardless, so the attribute mostly matters for the non-generic
+/// `i32` paths and index guards.
+pub struct FastTree<K: FastKey = i32> {
+    ptr: *mut c_void,
+    /// Copy of the keys of a tree of at most `TINY_MAX` keys, which
+    /// `search` and `lower_bound` scan in Rust instead of calling into C.
+    /// Boxed so large trees do not carry the unused array.
+    tiny: Option<Box<TinyKeys<K>>>,
+    _keys: PhantomData<K>,
 }
 
+/// Which build of the C library the FFI declarations link against:
+/// `"avx512"` or `"avx2"` for a library built with the matching
+/// `FAST_SYMBOL_PREFIX`, or `"default"` for the plain `fast_*` symbols.
+pub fn isa_variant() -> &'static str {
+    if cfg!(feature = "avx512") {
+        "avx512"
+    } else if cfg!(feature = "avx2") {
+        "avx2"
+    } else {
+        "default"
+    }
+}
+
+/// A FAST tree over `i64` keys, for data such as timestamps that exceed the
+/// `i32` range. The C side searches it with scalar comparisons.
+pub type FastTree64 = FastTree<i64>;
+
 // SAFETY: The underlying C library is thread-safe for read-only operations
 // after construction.
-unsafe impl Send for FastTree {}
-unsafe impl Sync for FastTree {}
+unsafe impl<K: FastKey> Send for FastTree<K> {}
+unsafe impl<K: FastKey> Sync for FastTree<K> {}
 
-impl FastTree {
-    /// Build a FAST tree from a sorted slice of 32-bit keys.
-    pub fn new(keys: &[i32]) -> Option<Self> {
+impl<K: FastKey> FastTree<K> {
+    /// Build a FAST tree from a sorted slice of keys.
+    ///
+    /// Returns `None` on any failure; use [`try_new`](FastTree::try_new) to
+    /// find out why.
+    ///
+    /// `keys` needs only the ordinary alignment of `K`; any sub-slice will
+    /// do. The C constructor reads it once, with plain copies, into a
+    /// layout it allocates itself at 64-byte (cache line) or page
+    /// alignment, and the SIMD search loads only from that layout. So no
+    /// constructor needs an aligned input buffer, and none makes an aligned
+    /// copy.
+    pub fn new(keys: &[K]) -> Option<Self> {
+        Self::try_new(keys).ok()
+    }
+
+    /// Build a FAST tree from a sorted slice of keys, panicking on
+    /// failure.
+    ///
+    /// For input known to be valid (constants, test fixtures, keys this
+    /// program just sorted), where a failure is a bug and should stop the
+    /// program, like `Vec::from`. Use [`new`](FastTree::new) when failure
+    /// needs no explanation and [`try_new`](FastTree::try_new) when it must
+    /// be reported or handled.
+    ///
+    /// # Panics
+    /// Panics with the [`FastTreeError`] message if `keys` is empty or
+    /// unsorted, or the C library fails to allocate.
+    pub fn new_or_panic(keys: &[K]) -> Self {
+        match Self::try_new(keys) {
+            Ok(tree) => tree,
+            Err(e) => panic!("FastTree::new_or_panic: {e}"),
+        }
+    }
+
+    /// Build a FAST tree from a sorted slice of keys, reporting why
+    /// construction failed.
+    ///
+    /// Empty input is [`FastTreeError::Empty`]; a null return from the C
+    /// constructor, or a slice longer than [`MAX_KEYS`], is
+    /// [`FastTreeError::AllocationFailed`].
+    pub fn try_new(keys: &[K]) -> Result<Self, FastTreeError> {
+        // Checked before the O(n) sortedness scan, so oversized input fails
+        // without being read.
+        check_len(keys.len())?;
+        if !is_sorted(keys) {
+            return Err(FastTreeError::Unsorted);
+        }
+        Self::build(keys)
+    }
+
+    /// Build a FAST tree from a sorted slice, reporting where the order
+    /// breaks if it is not sorted.
+    ///
+    /// On failure returns `(i, keys[i - 1], keys[i])` for the first `i`
+    /// with `keys[i - 1] > keys[i]`, which pinpoints the corruption in a
+    /// large input that [`try_new`](FastTree::try_new) would only call
+    /// [`Unsorted`](FastTreeError::Unsorted).
+    ///
+    /// Sorted input that still yields no tree, because it is empty or the
+    /// C library fails to allocate, gives `Ok(None)`, as
+    /// [`new`](FastTree::new) gives `None`; use `try_new` to tell those two
+    /// apart.
+    pub fn try_new_verbose(keys: &[K]) -> Result<Option<Self>, (usize, K, K)> {
+        if let Some(i) = keys.windows(2).position(|w| w[0] > w[1]) {
+            return Err((i + 1, keys[i], keys[i + 1]));
+        }
+        Ok(Self::build(keys).ok())
+    }
+
+    /// Build one tree per column, for columnar stores that index each
+    /// sorted column separately. Entry `i` is
+    /// [`new(columns[i])`](FastTree::new), so an empty or unsorted column
+    /// gives `None` without affecting the others.
+    ///
+    /// The columns are handed to the C constructor in place; it copies the
+    /// keys into each tree's own layout, so no staging buffer is needed.
+    pub fn new_many(columns: &[&[K]]) -> Vec<Option<Self>> {
+        columns.iter().map(|col| Self::new(col)).collect()
+    }
+
+    /// Build a FAST tree without checking that `keys` is sorted.
+    ///
+    /// For callers that already guarantee ordering and want to skip the
+    /// O(n) check. A tree built from unsorted keys returns meaningless
+    /// search results.
+    pub fn new_unchecked(keys: &[K]) -> Option<Self> {
+        Self::build(keys).ok()
+    }
+
+    /// Build a FAST tree from keys in any order.
+    ///
+    /// The keys are copied and sorted first. Indices returned by `search`,
+    /// `lower_bound` and friends refer to positions in that *sorted* order,
+    /// not to positions in `keys`; use [`sorted_keys`](FastTree::sorted_keys)
+    /// to map them back to key values.
+    pub fn from_unsorted(keys: &[K]) -> Option<Self> {
+        Self::from_vec(keys.to_vec()).ok()
+    }
+
+    /// Build a FAST tree from an owned `Vec` of keys in any order.
+    ///
+    /// Taking ownership lets unsorted input be sorted in place rather than
+    /// copied first; already-sorted input is used as is after an O(n)
+    /// check. The C library copies the keys into its own storage while
+    /// building, so the `Vec` is freed before this returns: peak memory is
+    /// the `Vec` plus the tree, and afterwards only the tree remains, with
+    /// [`keys`](FastTree::keys) still a zero-copy view of its copy.
+    pub fn from_vec(mut keys: Vec<K>) -> Result<Self, FastTreeError> {
+        if !is_sorted(&keys) {
+            keys.sort_unstable();
+        }
+        Self::build(&keys)
+    }
+
+    /// Build a FAST tree from an iterator that yields keys in sorted order,
+    /// without collecting them into a `Vec` first.
+    ///
+    /// Keys are buffered as they arrive and checked against their
+    /// predecessor, so an out-of-order key stops the iteration at once with
+    /// [`FastTreeError::Unsorted`] rather than after the whole input has
+    /// been read. An iterator that yields nothing is
+    /// [`FastTreeError::Empty`].
+    pub fn from_sorted_iter<I: IntoIterator<Item = K>>(iter: I) -> Result<Self, FastTreeError> {
+        let iter = iter.into_iter();
+        let mut keys = Vec::with_capacity(iter.size_hint().0);
+        for key in iter {
+            if keys.last().is_some_and(|&prev| prev > key) {
+                return Err(FastTreeError::Unsorted);
+            }
+            keys.push(key);
+        }
+        Self::build(&keys)
+    }
+
+    fn build(keys: &[K]) -> Result<Self, FastTreeError> {
         if keys.is_empty() {
-            return None;
+            return Err(FastTreeError::Empty);
         }
-        let ptr = unsafe { fast_create(keys.as_ptr(), keys.len()) };
+        check_len(keys.len())?;
+        let ptr = unsafe { K::create(keys.as_ptr(), keys.len()) };
         if ptr.is_null() {
-            None
+            Err(FastTreeError::AllocationFailed)
         } else {
-            Some(FastTree { ptr })
+            Ok(unsafe { Self::from_ptr(ptr) })
         }
     }
 
+    /// Wrap a live C tree, copying its keys for the Rust scan if it is
+    /// tiny.
+    ///
+    /// # Safety
+    /// `ptr` must be null or a live tree from `K::create`, owned by the
+    /// result from now on.
+    unsafe fn from_ptr(ptr: *mut c_void) -> Self {
+        let mut tree = FastTree {
+            ptr,
+            tiny: None,
+            _keys: PhantomData,
+        };
+        tree.tiny = TinyKeys::new(tree.sorted_keys()).map(Box::new);
+        tree
+    }
+
     /// Search for the largest key <= `key`. Returns the index or `None`.
-    pub fn search(&self, key: i32) -> Option<usize> {
-        let r = unsafe { fast_search(self.ptr, key) };
-        if r < 0 { None } else { Some(r as usize) }
+    ///
+    /// Every key value is valid, including the extremes: the C library
+    /// pads its layout with the type's maximum, but resolves queries at or
+    /// beyond either end of the stored keys before touching the layout. So
+    /// `search(K::MIN)` is `Some(0)` exactly when the smallest key is the
+    /// minimum, and `search(K::MAX)` is always the last index.
+    ///
+    /// The result is always `< size()`: an index the C library returns past
+    /// the end (which would mean a bug or an ABI mismatch) trips a debug
+    /// assertion and is reported as `None` in release builds, so it can
+    /// never reach [`key_at`](FastTree::key_at).
+    ///
+    /// When the matched key is duplicated, the index is that of any one of
+    /// its occurrences ([`DupPolicy::Any`]); use
+    /// [`search_with`](FastTree::search_with) to ask for the first or last.
+    #[inline]
+    #[must_use]
+    pub fn search(&self, key: K) -> Option<usize> {
+        if let Some(tiny) = &self.tiny {
+            return tiny.search(key);
+        }
+        self.search_ffi(key)
+    }
+
+    /// [`search`](FastTree::search) through the C library whatever the
+    /// tree's size, for cross-checking and benchmarking the FFI.
+    #[inline]
+    pub(crate) fn search_ffi(&self, key: K) -> Option<usize> {
+        let r = unsafe { K::search(self.ptr, key) };
+        self.checked_index(r)
+    }
+
+    /// Map a C search result to an index, treating -1 as `None` and
+    /// guarding against indices `>= size()`.
+    fn checked_index(&self, r: i64) -> Option<usize> {
+        let i = index_below(r, self.size());
+        debug_assert!(r < 0 || i.is_some(), "C search returned {r} for a tree of {}", self.size());
+        i
+    }
+
+    /// Like [`search`](FastTree::search), but always a valid index: a query
+    /// below the smallest key clamps to 0 instead of `None`.
+    ///
+    /// Queries above the largest key give `size() - 1`, as with `search`.
+    /// For interpolation and similar loops that want a bracketing index
+    /// either way, this removes the `None` branch; callers that must tell
+    /// "below the minimum" apart from "at the minimum" should use `search`.
+    #[must_use]
+    pub fn search_clamped(&self, key: K) -> usize {
+        self.search(key).unwrap_or(0)
+    }
+
+    /// Search for the largest key <= `key`, returning its index and the key
+    /// itself, or `None` if every key is greater.
+    ///
+    /// One call into the C library instead of `search` followed by
+    /// `key_at`, for the common "find the floor and use it" pattern.
+    #[must_use]
+    pub fn search_entry(&self, key: K) -> Option<(usize, K)> {
+        if let Some(tiny) = &self.tiny {
+            return tiny.search(key).map(|i| (i, tiny.key(i)));
+        }
+        let mut found = key;
+        let r = unsafe { K::search_with_key(self.ptr, key, &mut found) };
+        self.checked_index(r).map(|i| (i, found))
+    }
+
+    /// Search for each key in `keys`, writing the result of [`search`] for
+    /// `keys[i]` into `out[i]`.
+    ///
+    /// # Panics
+    /// Panics if `keys` and `out` differ in length.
+    ///
+    /// [`search`]: FastTree::search
+    pub fn search_many(&self, keys: &[K], out: &mut [Option<usize>]) {
+        if self.tiny.is_some() {
+            return self.query_many("search_many", keys, out, Self::search);
+        }
+        assert_eq!(keys.len(), out.len(), "search_many: keys and out differ in length");
+        // Staged through a stack buffer, so the batch entry point (which
+        // interleaves queries to overlap their cache misses) needs no
+        // allocation per call.
+        let mut raw = [0i64; SEARCH_BATCH_CHUNK];
+        let chunks = keys.chunks(SEARCH_BATCH_CHUNK).zip(out.chunks_mut(SEARCH_BATCH_CHUNK));
+        for (keys, out) in chunks {
+            let raw = &mut raw[..keys.len()];
+            unsafe { K::search_batch(self.ptr, keys, raw) };
+            for (slot, &r) in out.iter_mut().zip(raw.iter()) {
+                *slot = self.checked_index(r);
+            }
+        }
+    }
+
+    /// Like [`search_many`](FastTree::search_many), but writes the matched
+    /// key itself, the result of [`search_le_key`], instead of its index:
+    /// `out[i]` is the largest stored key <= `queries[i]`, or `None`.
+    ///
+    /// Callers after values save the second pass of `key_at` over the
+    /// indices, and the batch shape leaves room for a fused C entry point.
+    ///
+    /// # Panics
+    /// Panics if `queries` and `out` differ in length.
+    ///
+    /// [`search_le_key`]: FastTree::search_le_key
+    pub fn search_batch_keys(&self, queries: &[K], out: &mut [Option<K>]) {
+        self.query_many("search_batch_keys", queries, out, Self::search_le_key);
+    }
+
+    /// Run `query` for each key, writing results into `out`: the batch
+    /// entry points that have no C batch search loop through here.
+    fn query_many<T>(&self, name: &str, keys: &[K], out: &mut [T], query: fn(&Self, K) -> T) {
+        assert_eq!(keys.len(), out.len(), "{name}: keys and out differ in length");
+        for (slot, &key) in out.iter_mut().zip(keys) {
+            *slot = query(self, key);
+        }
+    }
+
+    /// Search for each key in `keys`, returning the results in order.
+    #[must_use]
+    pub fn search_batch(&self, keys: &[K]) -> Vec<Option<usize>> {
+        let mut out = vec![None; keys.len()];
+        self.search_many(keys, &mut out);
+        out
+    }
+
+    /// Like [`search_batch`](FastTree::search_batch), but fills a
+    /// caller-provided buffer so query loops can reuse one allocation.
+    ///
+    /// `out` is cleared, then filled with exactly `keys.len()` results; its
+    /// capacity is kept and only grows when a batch is larger than any
+    /// before it.
+    pub fn search_batch_into(&self, keys: &[K], out: &mut Vec<Option<usize>>) {
+        out.clear();
+        out.resize(keys.len(), None);
+        self.search_many(keys, out);
     }
 
     /// Find the first key >= `key`. Returns the index (may equal `size()`).
-    pub fn lower_bound(&self, key: i32) -> usize {
-        unsafe { fast_search_lower_bound(self.ptr, key) as usize }
+    ///
+    /// `lower_bound(K::MAX)` is `size()` unless the maximum itself is
+    /// stored, in which case it is the index of its first occurrence.
+    ///
+    /// As with [`search`](FastTree::search), a C result past `size()` trips
+    /// a debug assertion; release builds clamp it to `size()`.
+    #[inline]
+    #[must_use]
+    pub fn lower_bound(&self, key: K) -> usize {
+        if let Some(tiny) = &self.tiny {
+            return tiny.lower_bound(key);
+        }
+        self.lower_bound_ffi(key)
+    }
+
+    /// [`lower_bound`](FastTree::lower_bound) through the C library whatever
+    /// the tree's size.
+    #[inline]
+    pub(crate) fn lower_bound_ffi(&self, key: K) -> usize {
+        let r = unsafe { K::lower_bound(self.ptr, key) };
+        let n = self.size();
+        let i = index_upto(r, n);
+        debug_assert!(i.is_some(), "C lower_bound returned {r} for a tree of {n}");
+        i.unwrap_or(if r < 0 { 0 } else { n })
+    }
+
+    /// Find the first key >= each key in `keys`, writing the result of
+    /// [`lower_bound`] for `keys[i]` into `out[i]`.
+    ///
+    /// # Panics
+    /// Panics if `keys` and `out` differ in length.
+    ///
+    /// [`lower_bound`]: FastTree::lower_bound
+    pub fn lower_bound_many(&self, keys: &[K], out: &mut [usize]) {
+        self.query_many("lower_bound_many", keys, out, Self::lower_bound);
+    }
+
+    /// Lower bound of each key in `keys`, returning the results in order.
+    #[must_use]
+    pub fn lower_bound_batch(&self, keys: &[K]) -> Vec<usize> {
+        let mut out = vec![0; keys.len()];
+        self.lower_bound_many(keys, &mut out);
+        out
+    }
+
+    /// Like [`lower_bound_batch`](FastTree::lower_bound_batch), filling a
+    /// reused buffer under the same contract as
+    /// [`search_batch_into`](FastTree::search_batch_into).
+    pub fn lower_bound_batch_into(&self, keys: &[K], out: &mut Vec<usize>) {
+        out.clear();
+        out.resize(keys.len(), 0);
+        self.lower_bound_many(keys, out);
+    }
+
+    /// [`lower_bound_many`](FastTree::lower_bound_many) for queries sorted
+    /// in ascending order, as in a merge join.
+    ///
+    /// Instead of starting every search at the root, each query resumes
+    /// from the previous query's bound and gallops forward over the sorted
+    /// key array: it doubles its step until it passes the query, then
+    /// binary-searches the last step. The bound only ever moves forward, so
+    /// a batch costs O(n + m) at worst for `n` keys and `m` queries, and
+    /// O(m log(n / m)) when the queries are spread out, against
+    /// O(m log n) for independent searches.
+    ///
+    /// # Panics
+    /// Panics if `sorted_queries` and `out` differ in length, or if
+    /// `sorted_queries` is not sorted.
+    pub fn merge_lower_bounds(&self, sorted_queries: &[K], out: &mut [usize]) {
+        assert_eq!(
+            sorted_queries.len(),
+            out.len(),
+            "FastTree::merge_lower_bounds: queries and out differ in length"
+        );
+        assert!(
+            is_sorted(sorted_queries),
+            "FastTree::merge_lower_bounds: queries are not sorted"
+        );
+        let keys = self.sorted_keys();
+        let mut pos = 0;
+        for (slot, &q) in out.iter_mut().zip(sorted_queries) {
+            let rest = &keys[pos..];
+            // Invariant: every key before rest[bound / 2] is < q.
+            let mut bound = 1;
+            while bound <= rest.len() && rest[bound - 1] < q {
+                bound *= 2;
+            }
+            let (lo, hi) = (bound / 2, bound.min(rest.len()));
+            pos += lo + rest[lo..hi].partition_point(|&k| k < q);
+            *slot = pos;
+        }
+    }
+
+    /// Find the first key > `key`. Returns the index (may equal `size()`).
+    #[must_use]
+    pub fn upper_bound(&self, key: K) -> usize {
+        // First key > k is the first key >= k + 1; at the type's maximum
+        // nothing is greater, so every key is <= it.
+        match key.checked_next() {
+            Some(next) => self.lower_bound(next),
+            None => self.size(),
+        }
+    }
+
+    /// The largest key <= `key`, or `None` if every key is greater.
+    ///
+    /// Same as `search` followed by `key_at`, but the key is read from the
+    /// borrowed key array rather than through a second FFI call.
+    #[must_use]
+    pub fn search_le_key(&self, key: K) -> Option<K> {
+        self.search(key).map(|i| self.sorted_keys()[i])
+    }
+
+    /// The smallest key >= `key`, or `None` if every key is smaller.
+    #[must_use]
+    pub fn search_ge_key(&self, key: K) -> Option<K> {
+        self.sorted_keys().get(self.lower_bound(key)).copied()
+    }
+
+    /// The largest key strictly less than `key`, with its index, or `None`
+    /// if no key is smaller. Among duplicates this is the last occurrence.
+    #[must_use]
+    pub fn predecessor(&self, key: K) -> Option<(usize, K)> {
+        match self.lower_bound(key) {
+            0 => None,
+            i => Some((i - 1, self.key_at(i - 1))),
+        }
+    }
+
+    /// The smallest key strictly greater than `key`, with its index, or
+    /// `None` if no key is larger. Among duplicates this is the first
+    /// occurrence.
+    #[must_use]
+    pub fn successor(&self, key: K) -> Option<(usize, K)> {
+        let i = self.upper_bound(key);
+        if i < self.size() {
+            Some((i, self.key_at(i)))
+        } else {
+            None
+        }
+    }
+
+    /// Number of keys strictly less than `key`; the same value as
+    /// [`lower_bound`](FastTree::lower_bound).
+    ///
+    /// When `key` is duplicated, its rank is the index of its first
+    /// occurrence, so `select(rank(k)) == Some(k)` for every key present in
+    /// the tree.
+    #[must_use]
+    pub fn rank(&self, key: K) -> usize {
+        self.lower_bound(key)
+    }
+
+    /// Fraction of keys strictly less than `key`, `rank(key) / size()`, as
+    /// a selectivity estimate for query planning: 0.0 at or below the
+    /// smallest key, 1.0 above the largest. An empty tree gives 0.0.
+    #[must_use]
+    pub fn fraction_below(&self, key: K) -> f64 {
+        let n = self.size();
+        if n == 0 { 0.0 } else { self.rank(key) as f64 / n as f64 }
+    }
+
+    /// The `n`-th smallest key (0-based, duplicates counted separately), or
+    /// `None` if `n >= size()`.
+    #[must_use]
+    pub fn select(&self, n: usize) -> Option<K> {
+        if n < self.size() { Some(self.key_at(n)) } else { None }
+    }
+
+    /// The key closest to `key`, with its index, breaking ties toward the
+    /// lower key.
+    ///
+    /// Queries below the smallest key return the first key and queries
+    /// above the largest return the last; an exact match has distance zero
+    /// and wins outright. Distances are computed without overflow, so
+    /// `nearest(i32::MIN)` on a tree holding `i32::MAX` is fine. Returns
+    /// `None` only for an empty tree.
+    #[must_use]
+    pub fn nearest(&self, key: K) -> Option<(usize, K)> {
+        let keys = self.sorted_keys();
+        let i = self.lower_bound(key);
+        match (i.checked_sub(1), keys.get(i)) {
+            (None, None) => None,
+            (None, Some(&hi)) => Some((i, hi)),
+            (Some(lo_i), None) => Some((lo_i, keys[lo_i])),
+            (Some(lo_i), Some(&hi)) => {
+                let lo = keys[lo_i];
+                if key.distance(lo) <= hi.distance(key) {
+                    Some((lo_i, lo))
+                } else {
+                    Some((i, hi))
+                }
+            }
+        }
     }
 
-    /// Number of keys in the tree.
+    /// Up to `k` keys closest to `key`, with their indices, in order of
+    /// increasing distance and breaking ties toward the lower key like
+    /// [`nearest`](FastTree::nearest).
+    ///
+    /// Two cursors start at [`lower_bound(key)`](FastTree::lower_bound) and
+    /// walk outward, each step taking whichever side's next key is closer,
+    /// so the cost is O(log n + k). Fewer than `k` entries come back only
+    /// when the tree holds fewer than `k` keys; queries outside the key
+    /// range simply walk one side. Distances are computed without overflow.
+    #[must_use]
+    pub fn nearest_k(&self, key: K, k: usize) -> Vec<(usize, K)> {
+        let keys = self.sorted_keys();
+        let k = k.min(keys.len());
+        let mut out = Vec::with_capacity(k);
+        // keys[..left] and keys[right..] are still unvisited.
+        let mut left = self.lower_bound(key);
+        let mut right = left;
+        while out.len() < k {
+            let take_left = match (left.checked_sub(1), keys.get(right)) {
+                (Some(l), Some(&hi)) => key.distance(keys[l]) <= hi.distance(key),
+                (Some(_), None) => true,
+                (None, _) => false,
+            };
+            if take_left {
+                left -= 1;
+                out.push((left, keys[left]));
+            } else {
+                out.push((right, keys[right]));
+                right += 1;
+            }
+        }
+        out
+    }
+
+    /// The indices bracketing `key`: the largest key <= `key` and the
+    /// smallest key >= `key`, for interpolation between neighbours.
+    ///
+    /// An exact match puts both at the same index (the first occurrence of
+    /// a duplicated key, so that the pair stays a valid bracket). Below the
+    /// smallest key the lower side is `None`; above the largest, the upper.
+    #[must_use]
+    pub fn bracket(&self, key: K) -> (Option<usize>, Option<usize>) {
+        let hi = self.lower_bound(key);
+        let upper = (hi < self.size()).then_some(hi);
+        match upper {
+            Some(i) if self.key_at(i) == key => (upper, upper),
+            _ => (hi.checked_sub(1), upper),
+        }
+    }
+
+    /// Write the key nearest to `keys[i]` into `out[i]`, with the tie and
+    /// clamping rules of [`nearest`](FastTree::nearest). This is the
+    /// quantization loop: mapping many inputs onto a codebook of keys.
+    ///
+    /// # Panics
+    /// Panics if `keys` and `out` differ in length, or if the tree is
+    /// empty.
+    pub fn nearest_many(&self, keys: &[K], out: &mut [K]) {
+        assert!(!self.is_empty() || keys.is_empty(), "FastTree::nearest_many: tree is empty");
+        self.query_many("nearest_many", keys, out, |tree, key| {
+            tree.nearest(key).map_or(key, |(_, k)| k)
+        });
+    }
+
+    /// Whether `key` is present in the tree.
+    #[must_use]
+    pub fn contains(&self, key: K) -> bool {
+        self.index_of(key).is_some()
+    }
+
+    /// Index of the first occurrence of `key`, or `None` if it is absent.
+    #[must_use]
+    pub fn index_of(&self, key: K) -> Option<usize> {
+        self.binary_search(key).ok()
+    }
+
+    /// Like [`slice::partition_point`]: the index of the first key for
+    /// which `pred` is false, or `size()` if it holds for every key.
+    ///
+    /// `pred` must be monotonic over the sorted keys (true, then false);
+    /// otherwise the result is some index, but not a meaningful one. This
+    /// binary-searches the sorted key array directly, so it serves
+    /// predicates the value-based C search cannot express. With
+    /// `|k| k <= key` it is the index one past the last key equal to `key`.
+    #[must_use]
+    pub fn partition_point<P: FnMut(K) -> bool>(&self, mut pred: P) -> usize {
+        self.sorted_keys().partition_point(|&k| pred(k))
+    }
+
+    /// Like [`slice::binary_search`]: `Ok(index)` if `key` is present,
+    /// otherwise `Err(insertion_point)`, where inserting `key` at that index
+    /// would keep the keys sorted.
+    ///
+    /// Unlike the slice method, a duplicated key always reports its first
+    /// occurrence, and the insertion point is always `lower_bound(key)`.
+    pub fn binary_search(&self, key: K) -> Result<usize, usize> {
+        let i = self.lower_bound(key);
+        if i < self.size() && self.key_at(i) == key {
+            Ok(i)
+        } else {
+            Err(i)
+        }
+    }
+
+    /// Indices of every occurrence of `key`, `lower_bound(key)..upper_bound(key)`,
+    /// like C++ `equal_range`. An absent key gives the empty range at its
+    /// insertion point.
+    #[must_use]
+    pub fn equal_range(&self, key: K) -> Range<usize> {
+        self.lower_bound(key)..self.upper_bound(key)
+    }
+
+    /// Number of keys in the tree; the C library's name for
+    /// [`len`](FastTree::len).
+    #[inline]
+    #[must_use]
     pub fn size(&self) -> usize {
-        unsafe { fast_size(self.ptr) }
+        unsafe { K::size(self.ptr) }
+    }
+
+    /// Number of keys in the tree. Same as [`size`](FastTree::size), under
+    /// the name Rust collections use.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.size()
+    }
+
+    /// Whether the tree holds no keys. Construction rejects empty input, so
+    /// this is `false` for every tree built through this crate's
+    /// constructors.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Heap bytes the tree occupies, as reported by the C library.
+    ///
+    /// This counts the blocked node array (padded to a complete tree and
+    /// rounded up to whole cache lines), the parallel rank table, the copy
+    /// of the sorted keys and the tree header. Only the node array is walked
+    /// by a search, so its share of this figure is the cache footprint that
+    /// decides whether queries stay in L2/L3; the rank table and keys are
+    /// touched once per query, at the leaf.
+    #[must_use]
+    pub fn memory_usage(&self) -> usize {
+        unsafe { K::memory_bytes(self.ptr) }
     }
 
     /// Get the key at the given sorted index.
-    pub fn key_at(&self, index: usize) -> i32 {
-        unsafe { fast_key_at(self.ptr, index) }
+    ///
+    /// `index` must be less than `size()`. This is the unchecked fast path:
+    /// nothing is checked on the Rust side, and an out-of-range index gets
+    /// whatever the C library returns for it (currently 0) rather than an
+    /// error. Use [`get`](FastTree::get) when the index may be out of range.
+    #[inline]
+    #[must_use]
+    pub fn key_at(&self, index: usize) -> K {
+        unsafe { K::key_at(self.ptr, index) }
+    }
+
+    /// The key at sorted index `index`, or `None` if `index >= size()`.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<K> {
+        self.sorted_keys().get(index).copied()
+    }
+
+    /// The smallest key, or `None` if the tree is empty.
+    #[must_use]
+    pub fn first(&self) -> Option<K> {
+        self.sorted_keys().first().copied()
+    }
+
+    /// The largest key, or `None` if the tree is empty.
+    #[must_use]
+    pub fn last(&self) -> Option<K> {
+        self.sorted_keys().last().copied()
+    }
+
+    /// The smallest key.
+    ///
+    /// # Panics
+    /// Panics if the tree is empty, which construction never allows; only a
+    /// tree obtained some other way (say from a future deserialize path)
+    /// could reach it. Use [`first`](FastTree::first) for an `Option`.
+    #[must_use]
+    pub fn min_key(&self) -> K {
+        self.first().expect("FastTree::min_key: tree is empty")
+    }
+
+    /// The largest key.
+    ///
+    /// # Panics
+    /// Panics if the tree is empty; see [`min_key`](FastTree::min_key).
+    #[must_use]
+    pub fn max_key(&self) -> K {
+        self.last().expect("FastTree::max_key: tree is empty")
+    }
+
+    /// All keys in sorted order, borrowed from the tree's own copy.
+    ///
+    /// Index `i` of this slice is the key that index `i` from `search` or
+    /// `lower_bound` refers to.
+    #[must_use]
+    pub fn sorted_keys(&self) -> &[K] {
+        let n = self.size();
+        if n == 0 {
+            // The C accessor may return NULL here, which a slice must not hold.
+            return &[];
+        }
+        unsafe { core::slice::from_raw_parts(K::sorted_keys(self.ptr), n) }
+    }
+
+    /// Zero-copy view of the keys, borrowed from the tree.
+    ///
+    /// The search layout itself is permuted into SIMD and page blocks and
+    /// padded with sentinels, but the C library also keeps the original
+    /// sorted array (that is what `fast_sorted_keys` returns), so this view
+    /// is always sorted and is the same slice as
+    /// [`sorted_keys`](FastTree::sorted_keys).
+    #[must_use]
+    pub fn keys(&self) -> &[K] {
+        self.sorted_keys()
+    }
+
+    /// Copy all keys, in sorted order, into a new `Vec`.
+    ///
+    /// This is a single copy out of the tree's key array rather than one
+    /// FFI call per key; borrow [`sorted_keys`](FastTree::sorted_keys)
+    /// instead when no owned copy is needed.
+    #[must_use]
+    pub fn to_vec(&self) -> Vec<K> {
+        self.sorted_keys().to_vec()
+    }
+
+    /// Check the tree's invariants: the stored keys are non-decreasing, and
+    /// for every stored key `search` and `lower_bound` land on an occurrence
+    /// of it, with the lower bound at its first occurrence.
+    ///
+    /// A debugging aid for trees built with [`new_unchecked`] or from
+    /// untrusted input; it runs two searches per key, so it costs
+    /// O(n log n).
+    ///
+    /// [`new_unchecked`]: FastTree::new_unchecked
+    #[must_use]
+    pub fn verify(&self) -> bool {
+        let keys = self.sorted_keys();
+        if !is_sorted(keys) {
+            return false;
+        }
+        keys.iter().enumerate().all(|(i, &k)| {
+            let lb = self.lower_bound(k);
+            lb <= i
+                && keys[lb] == k
+                && (lb == 0 || keys[lb - 1] < k)
+                && self.search(k).is_some_and(|s| keys[s] == k)
+        })
+    }
+
+    /// Consume the tree, returning its keys in sorted order.
+    ///
+    /// The keys are copied out in one pass (as in [`to_vec`](FastTree::to_vec))
+    /// and the C tree is then freed by the ordinary `Drop` of `self`, exactly
+    /// once.
+    pub fn into_keys(self) -> Vec<K> {
+        self.to_vec()
+    }
+
+    /// Build a compact copy of the tree with each distinct key stored once,
+    /// or `None` if the new tree cannot be allocated.
+    ///
+    /// Indices in the new tree count distinct keys, so they differ from
+    /// this tree's wherever a duplicated key came before. A tree with no
+    /// duplicates is copied as is.
+    #[must_use]
+    pub fn dedup(&self) -> Option<Self> {
+        let mut keys = self.to_vec();
+        keys.dedup();
+        Self::build(&keys).ok()
+    }
+
+    /// Split into a tree of the keys `< key` and a tree of the keys
+    /// `>= key`, each `None` if it would be empty.
+    ///
+    /// The split index is [`lower_bound(key)`](FastTree::lower_bound), and
+    /// each half is built straight from its slice of the already sorted
+    /// keys. This tree is left as it is.
+    #[must_use]
+    pub fn split_at_key(&self, key: K) -> (Option<Self>, Option<Self>) {
+        let (below, rest) = self.sorted_keys().split_at(self.lower_bound(key));
+        (Self::build(below).ok(), Self::build(rest).ok())
+    }
+
+    /// Wrap a tree built on the C side (for instance by another FFI layer),
+    /// taking ownership of it.
+    ///
+    /// # Safety
+    /// `ptr` must be a non-null tree returned by the C constructor for this
+    /// key type (`fast_create` for `i32`, `fast_create_i64` for `i64`) and
+    /// not yet destroyed. Ownership moves to the returned `FastTree`, which
+    /// destroys the tree on drop, so nothing else may free or keep using
+    /// it. Debug builds assert that `ptr` is non-null.
+    pub unsafe fn from_raw(ptr: *mut c_void) -> Self {
+        debug_assert!(!ptr.is_null(), "FastTree::from_raw: null tree pointer");
+        Self::from_ptr(ptr)
+    }
+
+    /// Release ownership of the C tree, returning its pointer without
+    /// destroying it.
+    ///
+    /// The caller becomes responsible for the tree: pass it back through
+    /// [`from_raw`](FastTree::from_raw), or free it with the C destructor
+    /// for this key type.
+    #[must_use = "dropping the raw pointer leaks the tree"]
+    pub fn into_raw(mut self) -> *mut c_void {
+        // The tiny-tree copy is Rust-owned and not part of what is handed
+        // out, so it is freed before Drop is suppressed; from_raw rebuilds
+        // it from the C tree.
+        self.tiny = None;
+        // ManuallyDrop rather than forget: the handle is nulled as it is
+        // handed out, so no copy of the pointer is left owning the tree.
+        let mut this = core::mem::ManuallyDrop::new(self);
+        let ptr = core::mem::replace(&mut this.ptr, core::ptr::null_mut());
+        debug_assert!(!ptr.is_null(), "FastTree::into_raw: tree already released");
+        ptr
+    }
+
+    /// Free the C tree and null the pointer, so a second call (or a later
+    /// `Drop`) is a no-op instead of a double free.
+    fn release(&mut self) {
+        let ptr = core::mem::replace(&mut self.ptr, core::ptr::null_mut());
+        self.tiny = None;
+        if !ptr.is_null() {
+            unsafe { K::destroy(ptr) };
+        }
+    }
+
+    /// Build a new tree from the keys for which `pred` returns `true`, or
+    /// `None` if no key passes (or the new tree cannot be allocated).
+    ///
+    /// The tree itself is immutable; this is the rebuild-on-update pattern.
+    /// Filtering keeps the keys in order, so no re-sort is needed and the
+    /// cost is one pass plus the build.
+    #[must_use]
+    pub fn filter<F: FnMut(K) -> bool>(&self, mut pred: F) -> Option<Self> {
+        let kept: Vec<K> = self.sorted_keys().iter().copied().filter(|&k| pred(k)).collect();
+        Self::build(&kept).ok()
+    }
+
+    /// Build a tree holding the keys of both `a` and `b`, keeping
+    /// duplicates. The two sorted key arrays are merged in O(n + m), with no
+    /// re-sort. Returns `None` only if the new tree cannot be allocated.
+    #[must_use]
+    pub fn merge(a: &Self, b: &Self) -> Option<Self> {
+        Self::build(&merge_sorted(a.sorted_keys(), b.sorted_keys(), false)).ok()
+    }
+
+    /// Like [`merge`](FastTree::merge), but each distinct key appears once
+    /// in the result, whether it was duplicated within one input or shared
+    /// by both.
+    #[must_use]
+    pub fn merge_dedup(a: &Self, b: &Self) -> Option<Self> {
+        Self::build(&merge_sorted(a.sorted_keys(), b.sorted_keys(), true)).ok()
+    }
+
+    /// Whether every key of `self` is also a key of `other`, treating both
+    /// as sets: duplicates count once.
+    ///
+    /// A merge walk over both sorted key arrays, O(n + m).
+    #[must_use]
+    pub fn is_subset_of(&self, other: &Self) -> bool {
+        let theirs = other.sorted_keys();
+        let mut j = 0;
+        for &k in self.sorted_keys() {
+            while j < theirs.len() && theirs[j] < k {
+                j += 1;
+            }
+            if j == theirs.len() || theirs[j] != k {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Whether `self` and `other` share no key, by a merge walk over both
+    /// sorted key arrays, O(n + m).
+    #[must_use]
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        let (a, b) = (self.sorted_keys(), other.sorted_keys());
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+            match a[i].cmp(&b[j]) {
+                core::cmp::Ordering::Less => i += 1,
+                core::cmp::Ordering::Greater => j += 1,
+                core::cmp::Ordering::Equal => return false,
+            }
+        }
+        true
+    }
+
+    /// Iterate over all keys in sorted order.
+    pub fn iter(&self) -> FastTreeIter<'_, K> {
+        FastTreeIter::new(self, 0, self.size())
+    }
+
+    /// Iterate over `(index, key)` pairs for every key, in sorted order;
+    /// [`range(..)`](FastTree::range) by another name.
+    ///
+    /// ```no_run
+    /// use fast_tree::FastTree;
+    /// let tree = FastTree::new(&[10, 20, 30]).unwrap();
+    /// let entries: Vec<_> = tree.entries().collect();
+    /// assert_eq!(entries, [(0, 10), (1, 20), (2, 30)]);
+    /// assert_eq!(tree.entries().next_back(), Some((2, 30)));
+    /// ```
+    pub fn entries(&self) -> FastTreeRange<'_, K> {
+        FastTreeRange::new(self, 0, self.size())
+    }
+
+    /// Iterate over the keys from [`lower_bound(key)`](FastTree::lower_bound)
+    /// to the end: every key >= `key`.
+    pub fn keys_from(&self, key: K) -> FastTreeIter<'_, K> {
+        FastTreeIter::new(self, self.lower_bound(key), self.size())
+    }
+
+    /// Iterate over the keys before [`lower_bound(key)`](FastTree::lower_bound):
+    /// every key < `key`.
+    pub fn keys_until(&self, key: K) -> FastTreeIter<'_, K> {
+        FastTreeIter::new(self, 0, self.lower_bound(key))
+    }
+
+    /// A [`Cursor`] positioned at [`lower_bound(key)`](FastTree::lower_bound):
+    /// the first key >= `key`, or past the end if there is none.
+    pub fn cursor_at(&self, key: K) -> Cursor<'_, K> {
+        Cursor::new(self, self.lower_bound(key))
+    }
+
+    /// Iterate over `(index, key)` pairs whose keys fall within `bounds`,
+    /// like `BTreeMap::range`. A range whose start lies past its end yields
+    /// nothing.
+    pub fn range<R: RangeBounds<K>>(&self, bounds: R) -> FastTreeRange<'_, K> {
+        let (start, end) = self.index_span(bounds);
+        FastTreeRange::new(self, start, end)
+    }
+
+    /// The keys within `bounds`, collected into a `Vec`.
+    ///
+    /// The eager counterpart of [`range`](FastTree::range): the index span
+    /// is found once and the keys are copied from the sorted key array in
+    /// bulk rather than fetched one by one. A range whose start lies past
+    /// its end gives an empty `Vec`.
+    #[must_use]
+    pub fn keys_in_range<R: RangeBounds<K>>(&self, bounds: R) -> Vec<K> {
+        let (start, end) = self.index_span(bounds);
+        self.sorted_keys()[start..end].to_vec()
+    }
+
+    /// Whether any key falls within `bounds`: `count_range(bounds) > 0`,
+    /// answered from the two bound indices alone. Useful as an
+    /// interval-overlap filter.
+    #[must_use]
+    pub fn contains_range<R: RangeBounds<K>>(&self, bounds: R) -> bool {
+        let (start, end) = self.index_span(bounds);
+        start < end
+    }
+
+    /// Number of keys within `bounds`, in O(log n) without iterating.
+    ///
+    /// Equal to `self.range(bounds).count()`; a range whose start lies past
+    /// its end counts 0.
+    #[must_use]
+    pub fn count_range<R: RangeBounds<K>>(&self, bounds: R) -> usize {
+        let (start, end) = self.index_span(bounds);
+        end - start
+    }
+
+    /// Translate key bounds into a half-open index span `[start, end)`,
+    /// with `start <= end`.
+    fn index_span<R: RangeBounds<K>>(&self, bounds: R) -> (usize, usize) {
+        let start = match bounds.start_bound() {
+            Bound::Included(&k) => self.lower_bound(k),
+            Bound::Excluded(&k) => self.upper_bound(k),
+            Bound::Unbounded => 0,
+        };
+        let end = match bounds.end_bound() {
+            Bound::Included(&k) => self.upper_bound(k),
+            Bound::Excluded(&k) => self.lower_bound(k),
+            Bound::Unbounded => self.size(),
+        };
+        (start, end.max(start))
     }
 }
 
-impl Drop for FastTree {
+impl FastTree {
+    /// Build a tree from sorted `u32` keys, stored biased by `-2^31` so
+    /// that their unsigned order becomes the signed order of the `i32`
+    /// tree. Returns `None` if `keys` is empty or unsorted.
+    ///
+    /// Query such a tree with [`search_u32`](FastTree::search_u32) and
+    /// [`lower_bound_u32`](FastTree::lower_bound_u32), which bias the query
+    /// the same way; indices mean the same as for any tree, but the stored
+    /// keys (as seen by [`sorted_keys`](FastTree::sorted_keys) and the
+    /// plain `i32` methods) are the biased values.
+    pub fn from_u32_sorted(keys: &[u32]) -> Option<Self> {
+        let biased: Vec<i32> = keys.iter().map(|&k| bias_u32(k)).collect();
+        Self::new(&biased)
+    }
+
+    /// [`search`](FastTree::search) for a tree built with
+    /// [`from_u32_sorted`](FastTree::from_u32_sorted).
+    #[inline]
+    #[must_use]
+    pub fn search_u32(&self, key: u32) -> Option<usize> {
+        self.search(bias_u32(key))
+    }
+
+    /// [`lower_bound`](FastTree::lower_bound) for a tree built with
+    /// [`from_u32_sorted`](FastTree::from_u32_sorted).
+    #[inline]
+    #[must_use]
+    pub fn lower_bound_u32(&self, key: u32) -> usize {
+        self.lower_bound(bias_u32(key))
+    }
+
+    /// Index span of every key whose top `mask_bits` bits equal those of
+    /// `prefix`, for keys that pack short tokens into their high bits.
+    ///
+    /// With `mask = !0 << (32 - mask_bits)` the matching keys are exactly
+    /// `prefix & mask ..= prefix | !mask`, a contiguous run in sorted order
+    /// (also when the sign bit is among the prefix bits), so the span is
+    /// `lower_bound(prefix & mask)..upper_bound(prefix | !mask)`. Bits of
+    /// `prefix` below the mask are ignored; `mask_bits == 0` matches every
+    /// key and `mask_bits == 32` is an exact-key lookup.
+    ///
+    /// # Panics
+    /// Panics if `mask_bits > 32`.
+    #[must_use]
+    pub fn prefix_range(&self, prefix: i32, mask_bits: u32) -> Range<usize> {
+        assert!(mask_bits <= 32, "prefix_range: mask_bits {mask_bits} exceeds 32");
+        if mask_bits == 0 {
+            // No fixed bits: `0..=-1` below would be empty, not everything.
+            return 0..self.size();
+        }
+        let mask = (u32::MAX << (32 - mask_bits)) as i32;
+        let (start, end) = self.index_span((prefix & mask)..=(prefix | !mask));
+        start..end
+    }
+
+    /// Depth of the tree: the number of levels of the complete binary tree
+    /// the keys are padded to, `ceil(log2(size() + 1))`. A search descends
+    /// `levels() / 2` SIMD blocks, so each doubling of the key count adds half
+    /// a SIMD comparison.
+    #[must_use]
+    pub fn levels(&self) -> usize {
+        unsafe { ffi::fast_levels(self.ptr.cast()) as usize }
+    }
+
+    /// Page blocking of the layout as `(depth, keys_per_block)`: each page
+    /// block is a subtree of `depth` levels holding `2^depth - 1` keys that
+    /// fit in one memory page (depth 10 for 4 KiB pages).
+    ///
+    /// Within a page block the FAST paper nests two fixed levels of
+    /// blocking: SIMD blocks of 2 levels (3 keys, one SSE compare) and
+    /// cache-line blocks of 4 levels (15 keys, one 64-byte line). Once
+    /// `levels()` exceeds the page depth, every further page-block level a
+    /// search crosses may cost a TLB miss, which is where throughput starts
+    /// to drop with tree size.
+    #[must_use]
+    pub fn page_blocking(&self) -> (usize, usize) {
+        let (mut d_k, mut d_l, mut d_p) = (0, 0, 0);
+        unsafe { ffi::fast_blocking(self.ptr.cast(), &mut d_k, &mut d_l, &mut d_p) };
+        let depth = d_p as usize;
+        (depth, (1usize << depth) - 1)
+    }
+
+    /// The blocks a [`search`](FastTree::search) for `key` compares
+    /// against, as offsets into the C library's blocked layout, root first.
+    ///
+    /// Each offset is the first slot of a 3-key SIMD block (or, for odd
+    /// depths, the single key at the bottom), so the path has
+    /// `ceil(levels() / 2)` entries. The layout is cache-line aligned, so
+    /// dividing an offset by 16 gives its 64-byte line, which shows the
+    /// blocking at work: consecutive blocks of a path share lines and pages
+    /// far more often than a plain binary search's probes would. Queries
+    /// outside the stored range are answered by boundary checks and give
+    /// an empty path.
+    ///
+    /// Available only with the `debug-trace` feature, which is off by
+    /// default.
+    #[cfg(feature = "debug-trace")]
+    pub fn explain_search(&self, key: i32) -> Vec<usize> {
+        let mut path = vec![0; self.levels().div_ceil(2)];
+        let len = unsafe {
+            ffi::fast_search_trace(self.ptr.cast(), key, path.as_mut_ptr(), path.len())
+        };
+        path.truncate(len);
+        path
+    }
+
+    /// Search for each key in `keys` with a single call into the C library's
+    /// `fast_search_batch`, returning its raw results: the index of the
+    /// largest key <= the query, or -1.
+    ///
+    /// The query pointer and output buffer cross the FFI boundary once for
+    /// the whole batch.
+    ///
+    /// There is no fallback to a per-query loop, deliberately: the binding
+    /// and the C library ship together (the crate `links = "fast"` against
+    /// the library in this repository, which always exports
+    /// `fast_search_batch`), and extern symbols are resolved when linking.
+    /// A library without the symbol fails the link rather than reaching
+    /// this call, so a run-time fallback could never run.
+    pub fn search_batch_ffi(&self, keys: &[i32]) -> Vec<i64> {
+        let mut out = vec![0i64; keys.len()];
+        unsafe {
+            ffi::fast_search_batch(
+                self.ptr.cast(),
+                keys.as_ptr(),
+                keys.len(),
+                out.as_mut_ptr(),
+            );
+        }
+        out
+    }
+}
+
+/// Merge two sorted slices, optionally dropping repeated keys.
+fn merge_sorted<K: FastKey>(a: &[K], b: &[K], dedup: bool) -> Vec<K> {
+    let mut out = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        let k = if j == b.len() || (i < a.len() && a[i] <= b[j]) {
+            i += 1;
+            a[i - 1]
+        } else {
+            j += 1;
+            b[j - 1]
+        };
+        if !dedup || out.last() != Some(&k) {
+            out.push(k);
+        }
+    }
+    out
+}
+
+/// Largest tree the C layer supports, in keys.
+///
+/// The C code records each node's sorted rank as an `int32_t` and pads the
+/// layout to at most `2^31 - 1` node slots, so longer inputs would overflow
+/// its index arithmetic. Constructors reject them with
+/// [`FastTreeError::AllocationFailed`] before reaching the C library. On
+/// 32-bit targets the limit is unreachable, as the keys alone would exceed
+/// the address space.
+///
+/// The crate assumes `usize` has at least 32 bits (checked at compile
+/// time), so every index below `MAX_KEYS` fits in `usize` on every
+/// supported target. The C library reports indices as `int64_t`; they are
+/// converted with `usize::try_from`, never truncating casts, so a value
+/// out of range for the target is caught like any other bad index.
+pub const MAX_KEYS: usize = i32::MAX as usize;
+
+const _: () = assert!(usize::BITS >= 32, "fast-tree needs a usize of at least 32 bits");
+
+/// Order-preserving map from `u32` to `i32`: subtract `2^31`, which is
+/// flipping the top bit.
+fn bias_u32(k: u32) -> i32 {
+    (k ^ (1 << 31)) as i32
+}
+
+/// `r` as an index if it is in `0..len`, else `None`.
+#[inline]
+fn index_below(r: i64, len: usize) -> Option<usize> {
+    usize::try_from(r).ok().filter(|&i| i < len)
+}
+
+/// `r` as an index if it is in `0..=len` (the range of a lower bound),
+/// else `None`.
+#[inline]
+fn index_upto(r: i64, len: usize) -> Option<usize> {
+    usize::try_from(r).ok().filter(|&i| i <= len)
+}
+
+/// Reject inputs the C layer cannot index; see [`MAX_KEYS`].
+fn check_len(len: usize) -> Result<(), FastTreeError> {
+    if len > MAX_KEYS { Err(FastTreeError::AllocationFailed) } else { Ok(()) }
+}
+
+fn is_sorted<K: FastKey>(keys: &[K]) -> bool {
+    keys.windows(2).all(|w| w[0] <= w[1])
+}
+
+/// Queries [`FastTree::search_many`] hands to the C batch search per call.
+const SEARCH_BATCH_CHUNK: usize = 256;
+
+/// Number of keys shown at each end of a large tree's `Debug` output.
+const DEBUG_EDGE_KEYS: usize = 4;
+
+/// Prints the size and the keys, eliding the middle of trees with more than
+/// `2 * DEBUG_EDGE_KEYS` keys so million-key trees stay readable.
+impl<K: FastKey> fmt::Debug for FastTree<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Keys<'a, K>(&'a [K]);
+
+        impl<K: fmt::Debug> fmt::Debug for Keys<'_, K> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let keys = self.0;
+                if keys.len() <= 2 * DEBUG_EDGE_KEYS {
+                    return f.debug_list().entries(keys).finish();
+                }
+                f.debug_list()
+                    .entries(&keys[..DEBUG_EDGE_KEYS])
+                    .entry(&format_args!(".."))
+                    .entries(&keys[keys.len() - DEBUG_EDGE_KEYS..])
+                    .finish()
+            }
+        }
+
+        f.debug_struct("FastTree")
+            .field("size", &self.size())
+            .field("keys", &Keys(self.sorted_keys()))
+            .finish()
+    }
+}
+
+/// Two trees are equal when they hold the same keys. Trees of different
+/// sizes compare in O(1); otherwise keys are compared in order, stopping at
+/// the first mismatch.
+impl<K: FastKey> PartialEq for FastTree<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.sorted_keys() == other.sorted_keys()
+    }
+}
+
+impl<K: FastKey> Eq for FastTree<K> {}
+
+/// Hashes the key count followed by every key, so trees that compare equal
+/// hash equally. This is O(n) per call; cache the hash if a large tree is
+/// looked up repeatedly.
+impl<K: FastKey> Hash for FastTree<K> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // A slice hashes its length before its elements.
+        self.sorted_keys().hash(state);
+    }
+}
+
+/// Cloning is O(n): the keys are read back and a new C tree is built from
+/// them, so the clone owns its own pointer and shares nothing with `self`.
+impl<K: FastKey> Clone for FastTree<K> {
+    fn clone(&self) -> Self {
+        Self::build(self.sorted_keys()).expect("FastTree::clone: allocation failed")
+    }
+}
+
+/// `tree[i]` is the key at sorted index `i`, like [`key_at`](FastTree::key_at)
+/// but returning a reference.
+///
+/// The reference points into the key array the C tree owns (the same
+/// storage as [`sorted_keys`](FastTree::sorted_keys)), so it borrows the
+/// tree: it cannot outlive it, and since the tree is never mutated after
+/// construction no aliasing issue arises.
+///
+/// # Panics
+/// Panics if `index >= size()`, like indexing a `Vec`.
+impl<K: FastKey> Index<usize> for FastTree<K> {
+    type Output = K;
+
+    fn index(&self, index: usize) -> &K {
+        &self.sorted_keys()[index]
+    }
+}
+
+/// Collects the keys, sorts them and builds a tree, like
+/// [`from_unsorted`](FastTree::from_unsorted).
+///
+/// # Panics
+/// `collect` has no way to report failure, so this panics if the iterator
+/// yields no keys. Use `from_unsorted` to get an `Option` instead.
+///
+/// There is deliberately no `Extend` impl: a FAST tree is immutable once
+/// built, so "extending" it means rebuilding from all keys. Gather the keys
+/// and call `from_unsorted` once instead.
+impl<K: FastKey> FromIterator<K> for FastTree<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        match Self::from_vec(iter.into_iter().collect()) {
+            Ok(tree) => tree,
+            Err(e) => panic!("FastTree::from_iter: {e}"),
+        }
+    }
+}
+
+/// Same as [`FastTree::try_new`]: the keys must be sorted, so
+/// `let tree: FastTree = keys.try_into()?;` works in generic code.
+impl<K: FastKey> TryFrom<&[K]> for FastTree<K> {
+    type Error = FastTreeError;
+
+    fn try_from(keys: &[K]) -> Result<Self, FastTreeError> {
+        Self::try_new(keys)
+    }
+}
+
+/// Like the slice conversion, rejecting unsorted keys with
+/// [`FastTreeError::Unsorted`] rather than sorting them as
+/// [`from_vec`](FastTree::from_vec) does. The keys are read in place
+/// without a copy; the C library keeps its own copy, so the `Vec` itself
+/// cannot be reused and is freed on return.
+impl<K: FastKey> TryFrom<Vec<K>> for FastTree<K> {
+    type Error = FastTreeError;
+
+    fn try_from(keys: Vec<K>) -> Result<Self, FastTreeError> {
+        Self::try_new(&keys)
+    }
+}
+
+/// Frees the C tree exactly once. The pointer is only ever owned by one
+/// `FastTree`: [`into_raw`](FastTree::into_raw) gives it up without
+/// freeing, and [`from_raw`](FastTree::from_raw)'s contract forbids
+/// wrapping a pointer that another owner will free.
+impl<K: FastKey> Drop for FastTree<K> {
     fn drop(&mut self) {
-        if !self.ptr.is_null() {
-            unsafe { fast_destroy(self.ptr) };
+        self.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_new_empty() {
+        assert_eq!(FastTree::<i32>::try_new(&[]).err(), Some(FastTreeError::Empty));
+        assert!(FastTree::<i32>::new(&[]).is_none());
+    }
+
+    #[test]
+    fn zero_length_is_empty_not_allocation_failure() {
+        assert_eq!(FastTree::<i32>::try_new(&[]).err(), Some(FastTreeError::Empty));
+        assert_eq!(FastTree::<i64>::try_new(&[]).err(), Some(FastTreeError::Empty));
+        assert_eq!(FastTree::<i32>::build(&[]).err(), Some(FastTreeError::Empty));
+    }
+
+    #[test]
+    fn try_new_rejects_unsorted() {
+        assert_eq!(
+            FastTree::try_new(&[1, 5, 3]).err(),
+            Some(FastTreeError::Unsorted)
+        );
+        assert!(FastTree::new(&[2, 1]).is_none());
+        assert!(FastTree::new(&[1, 1, 2, 2]).is_some());
+    }
+
+    #[test]
+    fn new_unchecked_skips_sort_check() {
+        let tree = FastTree::new_unchecked(&[1, 5, 3]).unwrap();
+        assert_eq!(tree.size(), 3);
+        assert!(FastTree::<i32>::new_unchecked(&[]).is_none());
+    }
+
+    #[test]
+    fn from_unsorted_sorts_first() {
+        let tree = FastTree::from_unsorted(&[9, 1, 7, 3, 5]).unwrap();
+        assert_eq!(tree.sorted_keys(), [1, 3, 5, 7, 9]);
+        assert_eq!(tree.search(7), Some(3));
+        assert_eq!(tree.sorted_keys()[tree.search(6).unwrap()], 5);
+        assert!(FastTree::<i32>::from_unsorted(&[]).is_none());
+    }
+
+    #[test]
+    fn from_vec_sorts_in_place_when_needed() {
+        let tree = FastTree::from_vec(vec![5, 1, 4, 1]).unwrap();
+        assert_eq!(tree.keys(), [1, 1, 4, 5]);
+        let sorted: Vec<i64> = (0..100).collect();
+        assert_eq!(FastTree::from_vec(sorted.clone()).unwrap().to_vec(), sorted);
+        assert_eq!(FastTree::<i32>::from_vec(Vec::new()).err(), Some(FastTreeError::Empty));
+    }
+
+    #[test]
+    fn from_sorted_iter_checks_order() {
+        let tree = FastTree::from_sorted_iter((0..500).map(|i| i * 2)).unwrap();
+        assert_eq!(tree.size(), 500);
+        assert_eq!(tree.search(7), Some(3));
+        assert_eq!(
+            FastTree::<i32>::from_sorted_iter(std::iter::empty()).err(),
+            Some(FastTreeError::Empty)
+        );
+
+        // Fails at the first out-of-order key without draining the rest.
+        let mut source = [1, 2, 2, 1, 5, 6].into_iter();
+        let err = FastTree::from_sorted_iter(source.by_ref()).err();
+        assert_eq!(err, Some(FastTreeError::Unsorted));
+        assert_eq!(source.next(), Some(5));
+    }
+
+    #[test]
+    fn len_and_is_empty() {
+        let tree = FastTree::new(&[5, 6, 7]).unwrap();
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree.len(), tree.size());
+        assert!(!tree.is_empty());
+    }
+
+    #[test]
+    fn error_display() {
+        assert_eq!(
+            FastTreeError::Empty.to_string(),
+            "cannot build a FAST tree from zero keys"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn error_is_std_error() {
+        let err: Box<dyn std::error::Error> = Box::new(FastTreeError::Unsorted);
+        assert_eq!(err.to_string(), "keys are not sorted in ascending order");
+    }
+
+    #[test]
+    fn search_batch_matches_scalar() {
+        let keys: Vec<i32> = (0..1000).map(|i| i * 3).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        let queries: Vec<i32> = (-10..3010).step_by(7).collect();
+        let batch = tree.search_batch(&queries);
+        assert_eq!(batch.len(), queries.len());
+        for (&q, &r) in queries.iter().zip(&batch) {
+            assert_eq!(r, tree.search(q), "query {q}");
+        }
+
+        // The i64 batch loops in Rust rather than calling a C batch search.
+        let wide = FastTree64::new(&keys.iter().map(|&k| i64::from(k) << 20).collect::<Vec<_>>())
+            .unwrap();
+        let wide_queries: Vec<i64> = queries.iter().map(|&q| i64::from(q) << 20).collect();
+        let wide_batch = wide.search_batch(&wide_queries);
+        assert_eq!(wide_batch, batch);
+    }
+
+    #[test]
+    #[should_panic(expected = "differ in length")]
+    fn search_many_length_mismatch() {
+        let tree = FastTree::new(&[1, 2, 3]).unwrap();
+        let mut out = [None; 2];
+        tree.search_many(&[1, 2, 3], &mut out);
+    }
+
+    #[test]
+    fn lower_bound_batch_matches_scalar() {
+        let keys: Vec<i32> = (0..1000).map(|i| i / 3 * 7).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        let queries: Vec<i32> = (-10..2400).step_by(5).collect();
+        let batch = tree.lower_bound_batch(&queries);
+        assert_eq!(batch.len(), queries.len());
+        for (&q, &r) in queries.iter().zip(&batch) {
+            assert_eq!(r, tree.lower_bound(q), "query {q}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "lower_bound_many: keys and out differ in length")]
+    fn lower_bound_many_length_mismatch() {
+        let tree = FastTree::new(&[1, 2, 3]).unwrap();
+        let mut out = [0; 4];
+        tree.lower_bound_many(&[1, 2, 3], &mut out);
+    }
+
+    #[test]
+    fn equal_range_spans_duplicates() {
+        let tree = FastTree::new(&[1, 3, 3, 3, 5, 7, 7]).unwrap();
+        assert_eq!(tree.equal_range(3), 1..4);
+        assert_eq!(tree.equal_range(7), 5..7);
+        assert_eq!(tree.equal_range(5), 4..5);
+        assert_eq!(tree.equal_range(4), 4..4);
+        assert_eq!(tree.equal_range(0), 0..0);
+        assert_eq!(tree.equal_range(i32::MAX), 7..7);
+        assert!(tree.equal_range(3).all(|i| tree.key_at(i) == 3));
+    }
+
+    #[test]
+    fn contains_and_index_of() {
+        let tree = FastTree::new(&[2, 4, 4, 4, 8]).unwrap();
+        assert!(tree.contains(2));
+        assert!(tree.contains(8));
+        assert!(!tree.contains(3));
+        assert!(!tree.contains(1));
+        assert!(!tree.contains(9));
+        assert_eq!(tree.index_of(4), Some(1));
+        assert_eq!(tree.index_of(8), Some(4));
+        assert_eq!(tree.index_of(i32::MIN), None);
+        assert_eq!(tree.index_of(i32::MAX), None);
+    }
+
+    #[test]
+    fn first_and_last() {
+        let tree = FastTree::new(&[-5, 0, 12, 40]).unwrap();
+        assert_eq!(tree.first(), Some(tree.key_at(0)));
+        assert_eq!(tree.last(), Some(tree.key_at(tree.size() - 1)));
+        assert_eq!(tree.first(), Some(-5));
+        assert_eq!(tree.last(), Some(40));
+
+        let single = FastTree::new(&[7]).unwrap();
+        assert_eq!(single.first(), single.last());
+    }
+
+    #[test]
+    fn min_and_max_key() {
+        let tree = FastTree::new(&[-7, 0, 3, 3, 11]).unwrap();
+        assert_eq!(tree.min_key(), -7);
+        assert_eq!(tree.max_key(), 11);
+    }
+
+    #[test]
+    #[should_panic(expected = "FastTree::min_key: tree is empty")]
+    fn min_key_empty_panics() {
+        // No constructor yields an empty tree, so fake one with a null
+        // pointer, which the C accessors treat as size 0.
+        let empty = FastTree::<i32> {
+            ptr: std::ptr::null_mut(),
+            tiny: None,
+            _keys: PhantomData,
+        };
+        let _ = empty.min_key();
+    }
+
+    #[test]
+    fn clone_answers_identically() {
+        let keys: Vec<i32> = (0..500).map(|i| i * 4 - 1000).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        let copy = tree.clone();
+        assert_ne!(tree.ptr, copy.ptr);
+        for q in -1010..1010 {
+            assert_eq!(copy.search(q), tree.search(q), "search {q}");
+            assert_eq!(copy.lower_bound(q), tree.lower_bound(q), "lower_bound {q}");
+        }
+        drop(tree);
+        assert_eq!(copy.search(0), Some(250));
+    }
+
+    #[test]
+    fn debug_small_tree() {
+        let tree = FastTree::new(&[1, 2, 3]).unwrap();
+        assert_eq!(format!("{tree:?}"), "FastTree { size: 3, keys: [1, 2, 3] }");
+    }
+
+    #[test]
+    fn debug_elides_large_tree() {
+        let keys: Vec<i32> = (0..1_000).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        assert_eq!(
+            format!("{tree:?}"),
+            "FastTree { size: 1000, keys: [0, 1, 2, 3, .., 996, 997, 998, 999] }"
+        );
+    }
+
+    #[test]
+    fn eq_by_contents() {
+        let a = FastTree::new(&[1, 2, 3, 4]).unwrap();
+        let b = FastTree::from_unsorted(&[4, 3, 2, 1]).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a, a.clone());
+        assert_ne!(a, FastTree::new(&[1, 2, 3, 5]).unwrap());
+        assert_ne!(a, FastTree::new(&[1, 2, 3]).unwrap());
+    }
+
+    #[test]
+    fn equal_trees_hash_equally() {
+        use std::collections::HashSet;
+
+        let mut set = HashSet::new();
+        assert!(set.insert(FastTree::new(&[1, 2, 3]).unwrap()));
+        assert!(!set.insert(FastTree::from_unsorted(&[3, 1, 2]).unwrap()));
+        assert!(set.insert(FastTree::new(&[1, 2]).unwrap()));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn collect_from_shuffled() {
+        // Multiplying by a unit mod 101 permutes 0..101.
+        let tree: FastTree = (0..101).map(|i| (i * 37) % 101).collect();
+        assert_eq!(tree.size(), 101);
+        for k in 0..101 {
+            assert_eq!(tree.search(k), Some(k as usize));
+        }
+        assert_eq!(tree.search(-1), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "zero keys")]
+    fn collect_empty_panics() {
+        let _tree: FastTree = std::iter::empty().collect();
+    }
+
+    #[test]
+    fn keys_view_is_sorted_and_full_length() {
+        let tree = FastTree::from_unsorted(&[50, -3, 17, 17, 8]).unwrap();
+        assert_eq!(tree.keys().len(), tree.size());
+        assert_eq!(tree.keys(), [-3, 8, 17, 17, 50]);
+        assert_eq!(tree.keys().as_ptr(), tree.sorted_keys().as_ptr());
+    }
+
+    #[test]
+    fn get_checks_bounds() {
+        let tree = FastTree::new(&[4, 8, 15]).unwrap();
+        assert_eq!(tree.get(0), Some(4));
+        assert_eq!(tree.get(2), Some(15));
+        assert_eq!(tree.get(3), None);
+        assert_eq!(tree.get(usize::MAX), None);
+    }
+
+    #[test]
+    fn verify_built_and_corrupt_trees() {
+        let keys: Vec<i32> = (0..2000).map(|i| i / 4 * 3).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        assert!(tree.verify());
+        assert!(FastTree::<i32>::from_bytes(&tree.to_bytes()).unwrap().verify());
+
+        // Swap two keys in a serialized blob, then force it past the sort
+        // check the way an unchecked load would.
+        let mut bytes = FastTree::new(&[1, 2, 3, 4]).unwrap().to_bytes();
+        bytes[16..20].copy_from_slice(&9i32.to_le_bytes());
+        assert_eq!(FastTree::<i32>::from_bytes(&bytes).err(), Some(FastTreeError::Unsorted));
+        let corrupt: Vec<i32> = bytes[16..]
+            .chunks_exact(4)
+            .map(|c| i32::from_le_bytes(c.try_into().unwrap()))
+            .collect();
+        assert!(!FastTree::new_unchecked(&corrupt).unwrap().verify());
+    }
+
+    #[test]
+    fn into_keys_consumes_tree() {
+        let tree = FastTree::from_unsorted(&[30, 10, 20, 10]).unwrap();
+        let n = tree.size();
+        let keys = tree.into_keys();
+        assert_eq!(keys.len(), n);
+        assert_eq!(keys, [10, 10, 20, 30]);
+    }
+
+    #[test]
+    fn filter_rebuilds_subset() {
+        let tree = FastTree::new(&(0..100).collect::<Vec<i32>>()).unwrap();
+        let even = tree.filter(|k| k % 2 == 0).unwrap();
+        assert_eq!(even.size(), 50);
+        assert!(even.iter().all(|k| k % 2 == 0));
+        assert_eq!(even.search(51), Some(25));
+        assert_eq!(even.search_le_key(51), Some(50));
+        assert!(!even.contains(51));
+        assert_eq!(tree.size(), 100);
+        assert!(tree.filter(|k| k > 1000).is_none());
+    }
+
+    #[test]
+    fn merge_disjoint_and_overlapping() {
+        let a = FastTree::new(&[1, 3, 5]).unwrap();
+        let b = FastTree::new(&[2, 4, 6, 8]).unwrap();
+        assert_eq!(FastTree::merge(&a, &b).unwrap().keys(), [1, 2, 3, 4, 5, 6, 8]);
+
+        let c = FastTree::new(&[3, 3, 4, 5, 9]).unwrap();
+        assert_eq!(FastTree::merge(&a, &c).unwrap().keys(), [1, 3, 3, 3, 4, 5, 5, 9]);
+        assert_eq!(FastTree::merge_dedup(&a, &c).unwrap().keys(), [1, 3, 4, 5, 9]);
+
+        let single = FastTree::new(&[4]).unwrap();
+        assert_eq!(FastTree::merge(&single, &b).unwrap().keys(), [2, 4, 4, 6, 8]);
+        assert_eq!(FastTree::merge_dedup(&b, &single).unwrap().keys(), [2, 4, 6, 8]);
+        assert_eq!(FastTree::merge(&single, &single).unwrap().keys(), [4, 4]);
+    }
+
+    #[test]
+    fn to_vec_matches_key_at() {
+        let keys: Vec<i32> = (0..300).map(|i| i * i).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        let v = tree.to_vec();
+        assert_eq!(v, keys);
+        assert!(v.iter().enumerate().all(|(i, &k)| tree.key_at(i) == k));
+    }
+
+    #[test]
+    fn upper_bound_distinct() {
+        let tree = FastTree::new(&[10, 20, 30, 40, 50]).unwrap();
+        assert_eq!(tree.upper_bound(5), 0);
+        assert_eq!(tree.upper_bound(10), 1);
+        assert_eq!(tree.upper_bound(25), 2);
+        assert_eq!(tree.upper_bound(50), 5);
+        assert_eq!(tree.upper_bound(100), 5);
+    }
+
+    #[test]
+    fn upper_bound_duplicates() {
+        let tree = FastTree::new(&[1, 3, 3, 3, 7]).unwrap();
+        assert_eq!(tree.lower_bound(3), 1);
+        assert_eq!(tree.upper_bound(3), 4);
+        assert_eq!(tree.upper_bound(2), 1);
+        assert_eq!(tree.upper_bound(7), 5);
+    }
+
+    #[test]
+    fn upper_bound_i32_max() {
+        let tree = FastTree::new(&[0, i32::MAX]).unwrap();
+        assert_eq!(tree.upper_bound(i32::MAX), 2);
+        assert_eq!(tree.upper_bound(i32::MAX - 1), 1);
+    }
+
+    #[test]
+    fn upper_bound_single_element() {
+        let tree = FastTree::new(&[42]).unwrap();
+        assert_eq!(tree.upper_bound(41), 0);
+        assert_eq!(tree.upper_bound(42), 1);
+        assert_eq!(tree.upper_bound(43), 1);
+    }
+
+    #[test]
+    fn predecessor_and_successor() {
+        let tree = FastTree::new(&[10, 20, 20, 20, 30]).unwrap();
+        assert_eq!(tree.predecessor(10), None);
+        assert_eq!(tree.predecessor(5), None);
+        assert_eq!(tree.predecessor(11), Some((0, 10)));
+        assert_eq!(tree.predecessor(20), Some((0, 10)));
+        assert_eq!(tree.predecessor(21), Some((3, 20)));
+        assert_eq!(tree.predecessor(i32::MAX), Some((4, 30)));
+
+        assert_eq!(tree.successor(30), None);
+        assert_eq!(tree.successor(i32::MAX), None);
+        assert_eq!(tree.successor(i32::MIN), Some((0, 10)));
+        assert_eq!(tree.successor(10), Some((1, 20)));
+        assert_eq!(tree.successor(20), Some((4, 30)));
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn count_range_bounds() {
+        let tree = FastTree::new(&[1, 3, 3, 3, 5, 7, 9]).unwrap();
+        assert_eq!(tree.count_range(..), 7);
+        assert_eq!(tree.count_range(3..=3), 3);
+        assert_eq!(tree.count_range(3..5), 3);
+        assert_eq!(tree.count_range(3..=5), 4);
+        assert_eq!(tree.count_range((Bound::Excluded(3), Bound::Included(9))), 3);
+        assert_eq!(tree.count_range(..4), 4);
+        assert_eq!(tree.count_range(4..4), 0);
+        assert_eq!(tree.count_range(8..2), 0);
+        assert_eq!(tree.count_range(10..), 0);
+        assert_eq!(tree.count_range(i32::MIN..=i32::MAX), 7);
+        for lo in 0..11 {
+            for hi in 0..11 {
+                assert_eq!(tree.count_range(lo..hi), tree.range(lo..hi).count());
+            }
+        }
+    }
+
+    #[test]
+    fn rank_and_select() {
+        let tree = FastTree::new(&[2, 4, 4, 4, 8]).unwrap();
+        assert_eq!(tree.rank(1), 0);
+        assert_eq!(tree.rank(2), 0);
+        assert_eq!(tree.rank(4), 1);
+        assert_eq!(tree.rank(5), 4);
+        assert_eq!(tree.rank(8), 4);
+        assert_eq!(tree.rank(9), 5);
+
+        assert_eq!(tree.select(0), Some(2));
+        assert_eq!(tree.select(3), Some(4));
+        assert_eq!(tree.select(4), Some(8));
+        assert_eq!(tree.select(5), None);
+        assert_eq!(tree.select(usize::MAX), None);
+
+        for &k in tree.sorted_keys() {
+            assert_eq!(tree.select(tree.rank(k)), Some(k));
+        }
+    }
+
+    #[test]
+    fn index_by_position() {
+        let tree = FastTree::new(&[3, 6, 9, 12]).unwrap();
+        for i in 0..tree.size() {
+            assert_eq!(tree[i], tree.key_at(i));
+        }
+        assert_eq!(tree[0], 3);
+        assert_eq!(tree[3], 12);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn index_out_of_range() {
+        let tree = FastTree::new(&[3, 6, 9, 12]).unwrap();
+        let _ = tree[4];
+    }
+
+    #[test]
+    fn memory_usage_scales_with_size() {
+        let small = FastTree::new(&[1, 2, 3]).unwrap();
+        let keys: Vec<i32> = (0..100_000).collect();
+        let large = FastTree::new(&keys).unwrap();
+        assert!(small.memory_usage() >= 3 * 3 * std::mem::size_of::<i32>());
+        assert!(large.memory_usage() >= keys.len() * 3 * std::mem::size_of::<i32>());
+        assert!(large.memory_usage() > small.memory_usage());
+
+        let wide_keys: Vec<i64> = (0..100_000).collect();
+        let wide = FastTree64::new(&wide_keys).unwrap();
+        assert!(wide.memory_usage() > large.memory_usage());
+    }
+
+    #[test]
+    fn prefix_range_by_high_bits() {
+        let keys = [0x0100_0000, 0x01ff_ffff, 0x0200_0000, 0x0234_5678, 0x02ff_0000, 0x0300_0000];
+        let tree = FastTree::new(&keys).unwrap();
+        assert_eq!(tree.prefix_range(0x0200_0000, 8), 2..5);
+        assert_eq!(tree.prefix_range(0x02ab_cdef, 8), 2..5);
+        assert_eq!(tree.prefix_range(0x0100_0000, 8), 0..2);
+        assert_eq!(tree.prefix_range(0x0400_0000, 8), 6..6);
+        assert_eq!(tree.prefix_range(0x0234_5678, 32), 3..4);
+        assert_eq!(tree.prefix_range(0x7fff_ffff, 0), 0..6);
+
+        // The sign bit as part of the prefix.
+        let signed = FastTree::new(&[i32::MIN, -5, -1, 0, 7]).unwrap();
+        assert_eq!(signed.prefix_range(-1, 1), 0..3);
+        assert_eq!(signed.prefix_range(0, 1), 3..5);
+    }
+
+    #[test]
+    #[should_panic(expected = "mask_bits 33 exceeds 32")]
+    fn prefix_range_rejects_wide_mask() {
+        let _ = FastTree::new(&[1]).unwrap().prefix_range(0, 33);
+    }
+
+    #[test]
+    fn try_new_verbose_reports_first_violation() {
+        let mut keys: Vec<i32> = (0..10_000).map(|i| i * 2).collect();
+        keys[6789] = -5;
+        keys[9000] = 0;
+        assert_eq!(FastTree::try_new_verbose(&keys).unwrap_err(), (6789, 13576, -5));
+        assert_eq!(FastTree::try_new_verbose(&[3, 1]).unwrap_err(), (1, 3, 1));
+        let tree = FastTree::try_new_verbose(&[1, 1, 2]).unwrap().unwrap();
+        assert_eq!(tree.sorted_keys(), [1, 1, 2]);
+    }
+
+    #[test]
+    fn try_new_verbose_empty_is_ok_none() {
+        assert!(matches!(FastTree::<i32>::try_new_verbose(&[]), Ok(None)));
+    }
+
+    #[test]
+    fn search_entry_returns_floor_key() {
+        let tree = FastTree::new(&[5, 10, 10, 20]).unwrap();
+        assert_eq!(tree.search_entry(4), None);
+        assert_eq!(tree.search_entry(5), Some((0, 5)));
+        assert_eq!(tree.search_entry(15), Some((2, 10)));
+        assert_eq!(tree.search_entry(i32::MAX), Some((3, 20)));
+        let wide = FastTree64::new(&[-(1 << 40), 1 << 40]).unwrap();
+        assert_eq!(wide.search_entry(0), Some((0, -(1 << 40))));
+        assert_eq!(wide.search_entry(i64::MIN), None);
+    }
+
+    #[test]
+    fn batch_into_reuses_buffer() {
+        let tree = FastTree::new(&[10, 20, 30]).unwrap();
+        let mut out = Vec::new();
+        tree.search_batch_into(&[5, 15, 25, 35], &mut out);
+        assert_eq!(out, [None, Some(0), Some(1), Some(2)]);
+        let cap = out.capacity();
+        tree.search_batch_into(&[30], &mut out);
+        assert_eq!(out, [Some(2)]);
+        assert_eq!(out.capacity(), cap);
+        tree.search_batch_into(&[], &mut out);
+        assert!(out.is_empty());
+
+        let mut lb = vec![99; 8];
+        tree.lower_bound_batch_into(&[10, 11, 31], &mut lb);
+        assert_eq!(lb, [0, 1, 3]);
+        tree.lower_bound_batch_into(&[0, 20], &mut lb);
+        assert_eq!(lb, [0, 1]);
+    }
+
+    #[test]
+    fn search_clamped_extremes_and_interior() {
+        let tree = FastTree::new(&[10, 20, 30]).unwrap();
+        assert_eq!(tree.search_clamped(i32::MIN), 0);
+        assert_eq!(tree.search_clamped(9), 0);
+        assert_eq!(tree.search_clamped(10), 0);
+        assert_eq!(tree.search_clamped(25), 1);
+        assert_eq!(tree.search_clamped(30), 2);
+        assert_eq!(tree.search_clamped(i32::MAX), 2);
+    }
+
+    #[test]
+    fn raw_round_trip() {
+        let keys = [3, 6, 9];
+        let raw = unsafe { crate::ffi::fast_create(keys.as_ptr(), keys.len()) };
+        assert!(!raw.is_null());
+        let tree = unsafe { FastTree::<i32>::from_raw(raw.cast()) };
+        assert_eq!(tree.search(7), Some(1));
+        let ptr = tree.into_raw();
+        assert_eq!(ptr, raw.cast());
+        assert_eq!(unsafe { crate::ffi::fast_size(ptr.cast()) }, 3);
+        drop(unsafe { FastTree::<i32>::from_raw(ptr) });
+    }
+
+    #[test]
+    fn release_is_idempotent() {
+        let mut tree = FastTree::new(&[1, 2, 3]).unwrap();
+        tree.release();
+        assert!(tree.ptr.is_null());
+        assert_eq!(tree.size(), 0);
+        tree.release();
+        // Dropping the released tree must not free it a third time.
+        drop(tree);
+    }
+
+    #[test]
+    fn into_raw_under_manually_drop_frees_once() {
+        use core::mem::ManuallyDrop;
+        let tree = FastTree::new(&[5, 10, 15, 20]).unwrap();
+        let raw = tree.into_raw();
+        let mut held = ManuallyDrop::new(unsafe { FastTree::<i32>::from_raw(raw) });
+        assert_eq!(held.search(12), Some(1));
+        // Hand ownership back out of the ManuallyDrop, then drop it once.
+        let again = unsafe { ManuallyDrop::take(&mut held) }.into_raw();
+        assert_eq!(again, raw);
+        drop(unsafe { FastTree::<i32>::from_raw(again) });
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn keys_in_range_bounds() {
+        let tree = FastTree::new(&[1, 3, 3, 5, 7, 9]).unwrap();
+        assert_eq!(tree.keys_in_range(3..7), [3, 3, 5]);
+        assert_eq!(tree.keys_in_range(3..=7), [3, 3, 5, 7]);
+        assert_eq!(tree.keys_in_range((Bound::Excluded(3), Bound::Unbounded)), [5, 7, 9]);
+        assert_eq!(tree.keys_in_range(..), tree.to_vec());
+        assert!(tree.keys_in_range(4..5).is_empty());
+        assert!(tree.keys_in_range(100..).is_empty());
+        assert!(tree.keys_in_range(7..3).is_empty());
+    }
+
+    #[test]
+    fn dedup_keeps_distinct_keys() {
+        let keys: Vec<i32> = (0..10_000).map(|i| i / 100 * 5).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        let compact = tree.dedup().unwrap();
+        assert_eq!(compact.size(), 100);
+        assert!(compact.memory_usage() < tree.memory_usage());
+        assert_eq!(compact.search(0), Some(0));
+        assert_eq!(compact.search(12), Some(2));
+        assert_eq!(compact.search(495), Some(99));
+        assert_eq!(compact.search(-1), None);
+        assert_eq!(compact.index_of(250), Some(50));
+        let distinct = FastTree::new(&[1, 2, 3]).unwrap();
+        assert_eq!(distinct.dedup().unwrap(), distinct);
+    }
+
+    #[test]
+    fn nearest_many_matches_nearest() {
+        let tree = FastTree::new(&[-100, 0, 10, 11, 50]).unwrap();
+        let inputs = [i32::MIN, -1000, -50, -49, 5, 6, 10, 30, 31, 49, 1000, i32::MAX];
+        let mut out = [0; 12];
+        tree.nearest_many(&inputs, &mut out);
+        let expected: Vec<i32> = inputs.iter().map(|&k| tree.nearest(k).unwrap().1).collect();
+        assert_eq!(out.to_vec(), expected);
+        assert_eq!(out[..4], [-100, -100, -100, 0]);
+        assert_eq!(out[11], 50);
+    }
+
+    #[test]
+    #[should_panic(expected = "nearest_many: keys and out differ in length")]
+    fn nearest_many_length_mismatch() {
+        let tree = FastTree::new(&[1, 2]).unwrap();
+        tree.nearest_many(&[1, 2], &mut [0]);
+    }
+
+    #[test]
+    fn length_over_max_keys_is_rejected() {
+        assert_eq!(check_len(MAX_KEYS), Ok(()));
+        assert_eq!(check_len(MAX_KEYS + 1), Err(FastTreeError::AllocationFailed));
+        assert_eq!(check_len(usize::MAX), Err(FastTreeError::AllocationFailed));
+    }
+
+    #[test]
+    fn fraction_below_selectivity() {
+        let tree = FastTree::new(&[10, 20, 20, 30]).unwrap();
+        assert_eq!(tree.fraction_below(i32::MIN), 0.0);
+        assert_eq!(tree.fraction_below(10), 0.0);
+        assert_eq!(tree.fraction_below(20), 0.25);
+        assert_eq!(tree.fraction_below(21), 0.75);
+        assert_eq!(tree.fraction_below(31), 1.0);
+    }
+
+    #[test]
+    fn new_many_builds_independent_columns() {
+        let a: Vec<i32> = (0..1000).map(|i| i * 2).collect();
+        let b = [5, 5, 9];
+        let c: [i32; 0] = [];
+        let d = [3, 1];
+        let trees = FastTree::new_many(&[&a, &b, &c, &d]);
+        assert_eq!(trees.len(), 4);
+        let ta = trees[0].as_ref().unwrap();
+        let tb = trees[1].as_ref().unwrap();
+        assert_eq!((ta.size(), tb.size()), (1000, 3));
+        assert_eq!(ta.search(7), Some(3));
+        assert_eq!(tb.search(7), Some(1));
+        assert_eq!(tb.search(9), Some(2));
+        assert!(trees[2].is_none() && trees[3].is_none());
+        assert!(FastTree::<i64>::new_many(&[]).is_empty());
+    }
+
+    #[test]
+    fn partition_point_predicates() {
+        let tree = FastTree::new(&[1, 3, 3, 3, 5, 8]).unwrap();
+        assert_eq!(tree.partition_point(|k| k <= 3), 4);
+        assert_eq!(tree.partition_point(|k| k < 3), 1);
+        assert_eq!(tree.partition_point(|k| k % 2 == 1), 5);
+        assert_eq!(tree.partition_point(|_| true), 6);
+        assert_eq!(tree.partition_point(|_| false), 0);
+        for q in 0..10 {
+            assert_eq!(tree.partition_point(|k| k < q), tree.lower_bound(q));
+            assert_eq!(tree.partition_point(|k| k <= q), tree.upper_bound(q));
+        }
+    }
+
+    #[test]
+    fn out_of_range_c_index_is_rejected() {
+        assert_eq!(index_below(-1, 3), None);
+        assert_eq!(index_below(0, 3), Some(0));
+        assert_eq!(index_below(2, 3), Some(2));
+        assert_eq!(index_below(3, 3), None);
+        assert_eq!(index_below(i64::MAX, 3), None);
+        assert_eq!(index_below(0, 0), None);
+    }
+
+    #[test]
+    fn out_of_range_c_result_through_search() {
+        use crate::key::stub::{StubKey, StubTree};
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        // More than TINY_MAX keys, so queries reach the stubbed FFI. Debug
+        // builds flag a bad C index with an assertion; release builds map
+        // it to `None` (search) or clamp it to `size()` (lower_bound).
+        let keys: Vec<StubKey> = (0..20).map(StubKey).collect();
+        for (search, lower_bound) in [(20, 21), (i64::MAX, i64::MAX)] {
+            let raw = StubTree { keys: keys.clone(), search, lower_bound }.into_raw();
+            let tree = unsafe { FastTree::<StubKey>::from_raw(raw) };
+            assert!(tree.tiny.is_none());
+            let s = catch_unwind(AssertUnwindSafe(|| tree.search(StubKey(5))));
+            let e = catch_unwind(AssertUnwindSafe(|| tree.search_entry(StubKey(5))));
+            let lb = catch_unwind(AssertUnwindSafe(|| tree.lower_bound(StubKey(5))));
+            let many = catch_unwind(AssertUnwindSafe(|| tree.search_batch(&[StubKey(5)])));
+            if cfg!(debug_assertions) {
+                assert!(s.is_err() && e.is_err() && lb.is_err(), "{search} {lower_bound}");
+                assert!(many.is_err());
+            } else {
+                assert_eq!(s.unwrap(), None);
+                assert_eq!(e.unwrap(), None);
+                assert_eq!(lb.unwrap(), 20);
+                assert_eq!(many.unwrap(), [None]);
+            }
         }
     }
+
+    #[test]
+    fn c_index_conversion_never_truncates() {
+        // A 64-bit result a truncating cast would turn into a small index
+        // on a 32-bit target (2^32 + 1 -> 1) must be rejected, not wrapped.
+        let wraps = (1i64 << 32) + 1;
+        assert_eq!(index_below(wraps, 3), None);
+        assert_eq!(index_upto(wraps, 3), None);
+        assert_eq!(index_upto(3, 3), Some(3));
+        assert_eq!(index_upto(0, 0), Some(0));
+        assert_eq!(index_upto(-1, 3), None);
+        assert_eq!(index_upto(MAX_KEYS as i64, MAX_KEYS), Some(MAX_KEYS));
+    }
+
+    #[test]
+    fn split_at_key_halves() {
+        let tree = FastTree::new(&[1, 3, 5, 5, 7, 9]).unwrap();
+        let (lo, hi) = tree.split_at_key(5);
+        assert_eq!(lo.unwrap().sorted_keys(), [1, 3]);
+        assert_eq!(hi.unwrap().sorted_keys(), [5, 5, 7, 9]);
+
+        let (lo, hi) = tree.split_at_key(1);
+        assert!(lo.is_none());
+        assert_eq!(hi.unwrap(), tree);
+
+        let (lo, hi) = tree.split_at_key(10);
+        assert_eq!(lo.unwrap(), tree);
+        assert!(hi.is_none());
+    }
+
+    #[test]
+    fn misaligned_input_slice() {
+        let keys: Vec<i32> = (0..4097).collect();
+        // Sixteen consecutive offsets hit every 4-byte position within a
+        // cache line, so all but one start misaligned.
+        for offset in 0..16 {
+            let sub = &keys[offset..];
+            let tree = FastTree::new(sub).unwrap();
+            assert_eq!(tree.search(2000), Some(2000 - offset));
+            assert_eq!(tree.cursor_at(0).key(), Some(offset as i32));
+        }
+    }
+
+    #[test]
+    fn contains_range_overlap() {
+        let tree = FastTree::new(&[10, 20, 30]).unwrap();
+        assert!(tree.contains_range(15..=25));
+        assert!(tree.contains_range(5..=100));
+        assert!(!tree.contains_range(..10));
+        assert!(!tree.contains_range(0..=9));
+        assert!(!tree.contains_range(31..));
+        assert!(!tree.contains_range(21..30));
+        assert!(tree.contains_range(30..=30));
+        assert!(tree.contains_range(..=10));
+    }
+
+    #[test]
+    #[cfg(feature = "debug-trace")]
+    fn explain_search_path() {
+        let keys: Vec<i32> = (0..1000).map(|i| i * 2).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        let path = tree.explain_search(777);
+        assert_eq!(path.len(), 5);
+        assert_eq!(path[0], 0);
+        assert!(path.windows(2).all(|w| w[0] < w[1]));
+        assert!(tree.explain_search(-1).is_empty());
+        assert!(tree.explain_search(1998).is_empty());
+
+        let odd = FastTree::new(&keys[..100]).unwrap();
+        assert_eq!(odd.levels(), 7);
+        assert_eq!(odd.explain_search(51).len(), 4);
+    }
+
+    #[test]
+    fn u32_keys_with_bias() {
+        let keys = [0, 1, 1 << 31, (1 << 31) + 5, u32::MAX - 1, u32::MAX];
+        let tree = FastTree::from_u32_sorted(&keys).unwrap();
+        assert_eq!(tree.sorted_keys()[0], i32::MIN);
+        assert_eq!(tree.sorted_keys()[5], i32::MAX);
+        assert_eq!(tree.search_u32(0), Some(0));
+        assert_eq!(tree.search_u32(100), Some(1));
+        assert_eq!(tree.search_u32((1 << 31) - 1), Some(1));
+        assert_eq!(tree.search_u32(1 << 31), Some(2));
+        assert_eq!(tree.search_u32(u32::MAX), Some(5));
+        assert_eq!(tree.lower_bound_u32(2), 2);
+        assert_eq!(tree.lower_bound_u32(u32::MAX), 5);
+        let high = FastTree::from_u32_sorted(&[u32::MAX]).unwrap();
+        assert_eq!(high.search_u32(0), None);
+        assert!(FastTree::from_u32_sorted(&[u32::MAX, 0]).is_none());
+    }
+
+    #[test]
+    fn bracket_neighbours() {
+        let tree = FastTree::new(&[10, 20, 20, 30]).unwrap();
+        assert_eq!(tree.bracket(20), (Some(1), Some(1)));
+        assert_eq!(tree.bracket(10), (Some(0), Some(0)));
+        assert_eq!(tree.bracket(30), (Some(3), Some(3)));
+        assert_eq!(tree.bracket(15), (Some(0), Some(1)));
+        assert_eq!(tree.bracket(25), (Some(2), Some(3)));
+        assert_eq!(tree.bracket(5), (None, Some(0)));
+        assert_eq!(tree.bracket(i32::MIN), (None, Some(0)));
+        assert_eq!(tree.bracket(31), (Some(3), None));
+        assert_eq!(tree.bracket(i32::MAX), (Some(3), None));
+    }
+
+    #[test]
+    fn new_or_panic_builds_valid_input() {
+        assert_eq!(FastTree::new_or_panic(&[1, 2, 3]).search(2), Some(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "FastTree::new_or_panic: cannot build a FAST tree from zero keys")]
+    fn new_or_panic_on_empty() {
+        FastTree::<i32>::new_or_panic(&[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "keys are not sorted")]
+    fn new_or_panic_on_unsorted() {
+        FastTree::new_or_panic(&[2, 1]);
+    }
+
+    #[test]
+    fn merge_lower_bounds_matches_lower_bound() {
+        let keys: Vec<i32> = (0..5000).map(|i| (i / 3) * 7).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        let mut rng = query_gen::Lcg::new(11);
+        let mut queries: Vec<i32> = (0..3000).map(|_| rng.next_i32_in(11_999) - 100).collect();
+        queries.extend([i32::MIN, i32::MAX, 0, 7, 7]);
+        queries.sort_unstable();
+        for qs in [&queries[..], &queries[..1], &queries[2990..], &[]] {
+            let mut out = vec![usize::MAX; qs.len()];
+            tree.merge_lower_bounds(qs, &mut out);
+            assert_eq!(out, tree.lower_bound_batch(qs));
+        }
+        let tiny = FastTree::new(&[3, 3, 9]).unwrap();
+        let mut out = [0; 5];
+        tiny.merge_lower_bounds(&[1, 3, 4, 9, 10], &mut out);
+        assert_eq!(out, [0, 0, 2, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "FastTree::merge_lower_bounds: queries are not sorted")]
+    fn merge_lower_bounds_rejects_unsorted() {
+        let tree = FastTree::new(&[1, 2, 3]).unwrap();
+        tree.merge_lower_bounds(&[2, 1], &mut [0; 2]);
+    }
+
+    #[test]
+    fn subset_and_disjoint() {
+        let t = |keys: &[i32]| FastTree::new(keys).unwrap();
+        let big = t(&[1, 3, 5, 7, 9]);
+        // Proper subset, with a duplicate that counts once.
+        assert!(t(&[3, 3, 7]).is_subset_of(&big));
+        assert!(!big.is_subset_of(&t(&[3, 7])));
+        // Equal sets.
+        assert!(big.is_subset_of(&t(&[1, 3, 5, 7, 9])));
+        assert!(!big.is_disjoint(&big));
+        // Disjoint sets.
+        let evens = t(&[0, 2, 4, 6, 8, 10]);
+        assert!(big.is_disjoint(&evens) && evens.is_disjoint(&big));
+        assert!(!evens.is_subset_of(&big));
+        // Partial overlap.
+        let mixed = t(&[2, 3, 4]);
+        assert!(!mixed.is_subset_of(&big) && !big.is_subset_of(&mixed));
+        assert!(!mixed.is_disjoint(&big) && !mixed.is_disjoint(&evens));
+        assert!(t(&[i32::MAX]).is_disjoint(&t(&[i32::MIN])));
+    }
+
+    #[test]
+    fn search_batch_keys_matches_search_le_key() {
+        let tree = FastTree::new(&[-10, 0, 0, 25, 100]).unwrap();
+        let queries = [i32::MIN, -11, -10, -1, 0, 24, 25, 99, 100, i32::MAX];
+        let mut out = [Some(7); 10];
+        tree.search_batch_keys(&queries, &mut out);
+        for (&q, &k) in queries.iter().zip(&out) {
+            assert_eq!(k, tree.search_le_key(q), "query {q}");
+        }
+        assert_eq!(out[..4], [None, None, Some(-10), Some(-10)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "search_batch_keys: keys and out differ in length")]
+    fn search_batch_keys_length_mismatch() {
+        let tree = FastTree::new(&[1, 2]).unwrap();
+        tree.search_batch_keys(&[1, 2], &mut [None]);
+    }
+
+    #[test]
+    fn isa_variant_matches_linked_build() {
+        // Building a tree proves the selected symbols resolved at link time.
+        let tree = FastTree::new(&[1, 2, 3]).unwrap();
+        assert_eq!(tree.search(2), Some(1));
+        let wide = FastTree64::new(&[1, 2, 3]).unwrap();
+        assert_eq!(wide.lower_bound(3), 2);
+        let expected = if cfg!(feature = "avx512") {
+            "avx512"
+        } else if cfg!(feature = "avx2") {
+            "avx2"
+        } else {
+            "default"
+        };
+        assert_eq!(super::isa_variant(), expected);
+    }
+
+    #[test]
+    fn try_from_slice_and_vec() {
+        let keys = vec![2, 4, 4, 8];
+        let tree: FastTree = keys.as_slice().try_into().unwrap();
+        assert_eq!(tree.sorted_keys(), keys);
+        let owned = FastTree::try_from(keys).unwrap();
+        assert_eq!(owned, tree);
+        let wide: FastTree64 = vec![-1i64, 1 << 40].try_into().unwrap();
+        assert_eq!(wide.search(0), Some(0));
+
+        let empty: &[i32] = &[];
+        assert_eq!(FastTree::try_from(empty).unwrap_err(), FastTreeError::Empty);
+        assert_eq!(FastTree::<i32>::try_from(Vec::new()).unwrap_err(), FastTreeError::Empty);
+        assert_eq!(FastTree::try_from(&[3, 1][..]).unwrap_err(), FastTreeError::Unsorted);
+        assert_eq!(FastTree::try_from(vec![3, 1]).unwrap_err(), FastTreeError::Unsorted);
+    }
+
+    #[test]
+    fn nearest_k_matches_brute_force() {
+        let keys = [i32::MIN, -50, -10, -10, 0, 3, 3, 7, 20, 21, 100, i32::MAX];
+        let tree = FastTree::new(&keys).unwrap();
+        for q in [i32::MIN, -60, -10, -5, 0, 1, 5, 14, 50, 1000, i32::MAX] {
+            for k in [0, 1, 2, 5, keys.len(), keys.len() + 3] {
+                let got = tree.nearest_k(q, k);
+                let mut expected: Vec<i32> = keys.to_vec();
+                expected.sort_by_key(|&x| ((i64::from(x) - i64::from(q)).abs(), x));
+                expected.truncate(k);
+                assert_eq!(got.iter().map(|&(_, x)| x).collect::<Vec<_>>(), expected, "{q} {k}");
+                let mut indices: Vec<usize> = got.iter().map(|&(i, _)| i).collect();
+                assert!(got.iter().all(|&(i, x)| keys[i] == x));
+                indices.sort_unstable();
+                indices.dedup();
+                assert_eq!(indices.len(), got.len());
+            }
+        }
+        assert_eq!(tree.nearest_k(3, 1)[0].1, tree.nearest(3).unwrap().1);
+    }
+
+    #[test]
+    fn levels_and_page_blocking() {
+        assert_eq!(FastTree::new(&[1]).unwrap().levels(), 1);
+        assert_eq!(FastTree::new(&[1, 2, 3]).unwrap().levels(), 2);
+        assert_eq!(FastTree::new(&[1, 2, 3, 4]).unwrap().levels(), 3);
+        let keys: Vec<i32> = (0..1000).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        assert_eq!(tree.levels(), 10);
+        let (depth, per_block) = tree.page_blocking();
+        assert!(depth >= 4);
+        assert_eq!(per_block, (1 << depth) - 1);
+    }
+
+    #[test]
+    fn search_batch_ffi_matches_scalar() {
+        let keys: Vec<i32> = (0..2000).map(|i| i * 5).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        let queries: Vec<i32> = (-20..10_020).step_by(3).collect();
+        let raw = tree.search_batch_ffi(&queries);
+        assert_eq!(raw.len(), queries.len());
+        for (&q, &r) in queries.iter().zip(&raw) {
+            assert_eq!(r, tree.search(q).map_or(-1, |i| i as i64), "query {q}");
+        }
+        assert!(tree.search_batch_ffi(&[]).is_empty());
+    }
+
+    #[test]
+    fn search_le_and_ge_key() {
+        let tree = FastTree::new(&[10, 20, 20, 30]).unwrap();
+        for q in 0..40 {
+            assert_eq!(tree.search_le_key(q), tree.search(q).map(|i| tree.key_at(i)), "le {q}");
+            let lb = tree.lower_bound(q);
+            let ge = if lb < tree.size() { Some(tree.key_at(lb)) } else { None };
+            assert_eq!(tree.search_ge_key(q), ge, "ge {q}");
+        }
+        assert_eq!(tree.search_le_key(25), Some(20));
+        assert_eq!(tree.search_ge_key(25), Some(30));
+        assert_eq!(tree.search_le_key(i32::MIN), None);
+        assert_eq!(tree.search_ge_key(i32::MAX), None);
+    }
+
+    #[test]
+    fn nearest_key() {
+        let tree = FastTree::new(&[10, 20, 20, 40]).unwrap();
+        assert_eq!(tree.nearest(-5), Some((0, 10)));
+        assert_eq!(tree.nearest(10), Some((0, 10)));
+        assert_eq!(tree.nearest(14), Some((0, 10)));
+        assert_eq!(tree.nearest(15), Some((0, 10)));
+        assert_eq!(tree.nearest(16), Some((1, 20)));
+        assert_eq!(tree.nearest(20), Some((1, 20)));
+        assert_eq!(tree.nearest(30), Some((2, 20)));
+        assert_eq!(tree.nearest(31), Some((3, 40)));
+        assert_eq!(tree.nearest(1000), Some((3, 40)));
+    }
+
+    #[test]
+    fn nearest_extremes_do_not_overflow() {
+        let tree = FastTree::new(&[i32::MIN, i32::MAX]).unwrap();
+        assert_eq!(tree.nearest(-1), Some((0, i32::MIN)));
+        assert_eq!(tree.nearest(0), Some((1, i32::MAX)));
+        assert_eq!(tree.nearest(i32::MAX - 1), Some((1, i32::MAX)));
+
+        let wide = FastTree64::new(&[i64::MIN, i64::MAX]).unwrap();
+        assert_eq!(wide.nearest(-1), Some((0, i64::MIN)));
+        assert_eq!(wide.nearest(1), Some((1, i64::MAX)));
+    }
+
+    #[test]
+    fn binary_search_like_slice() {
+        let keys = [10, 20, 20, 30];
+        let tree = FastTree::new(&keys).unwrap();
+        assert_eq!(tree.binary_search(10), Ok(0));
+        assert_eq!(tree.binary_search(20), Ok(1));
+        assert_eq!(tree.binary_search(30), Ok(3));
+        assert_eq!(tree.binary_search(15), Err(1));
+        assert_eq!(tree.binary_search(25), Err(3));
+        assert_eq!(tree.binary_search(i32::MIN), Err(0));
+        assert_eq!(tree.binary_search(31), Err(4));
+        for q in 5..35 {
+            assert_eq!(tree.binary_search(q).is_ok(), keys.binary_search(&q).is_ok());
+            if let Err(i) = tree.binary_search(q) {
+                assert_eq!(keys.binary_search(&q), Err(i));
+            }
+        }
+    }
+
+    #[test]
+    fn full_range_sentinels() {
+        // Sizes straddle SIMD-block and page-block boundaries, where the
+        // layout is padded with i32::MAX.
+        for n in 1..300usize {
+            let mut keys: Vec<i32> = (0..n as i32).map(|i| i * 1000 - 100_000).collect();
+            keys[0] = i32::MIN;
+            let last = if n > 1 { i32::MAX } else { i32::MIN };
+            keys[n - 1] = last;
+            let tree = FastTree::new(&keys).unwrap();
+            assert_eq!(tree.search(i32::MIN), Some(0), "n {n}");
+            assert_eq!(tree.lower_bound(i32::MIN), 0, "n {n}");
+            assert_eq!(tree.search(i32::MAX), Some(n - 1), "n {n}");
+            assert_eq!(tree.lower_bound(i32::MAX), if n > 1 { n - 1 } else { n }, "n {n}");
+            for (i, &k) in keys.iter().enumerate() {
+                assert_eq!(tree.search(k).map(|j| keys[j]), Some(k), "n {n}, key {i}");
+            }
+
+            let below_max: Vec<i32> = (0..n as i32).collect();
+            let tree = FastTree::new(&below_max).unwrap();
+            assert_eq!(tree.lower_bound(i32::MAX), n, "n {n}");
+            assert_eq!(tree.search(i32::MAX), Some(n - 1), "n {n}");
+            assert_eq!(tree.search(i32::MIN), None, "n {n}");
+        }
+    }
+
+    #[test]
+    fn all_keys_at_extremes() {
+        for n in [1usize, 2, 3, 16, 17, 100] {
+            let maxes = FastTree::new(&vec![i32::MAX; n]).unwrap();
+            assert_eq!(maxes.search(i32::MAX - 1), None);
+            assert!(maxes.search(i32::MAX).is_some());
+            assert_eq!(maxes.lower_bound(i32::MAX), 0);
+            assert_eq!(maxes.upper_bound(i32::MAX), n);
+
+            let mins = FastTree::new(&vec![i32::MIN; n]).unwrap();
+            assert!(mins.search(i32::MIN).is_some());
+            assert_eq!(mins.search(i32::MAX), Some(n - 1));
+            assert_eq!(mins.lower_bound(i32::MIN + 1), n);
+        }
+    }
+
+    #[test]
+    fn i64_search_beyond_i32_range() {
+        let keys: Vec<i64> = (0..1000).map(|i| 1_700_000_000_000 + i * 60_000).collect();
+        let tree = FastTree64::new(&keys).unwrap();
+        assert_eq!(tree.size(), 1000);
+        assert_eq!(tree.sorted_keys(), keys);
+        for (i, &k) in keys.iter().enumerate() {
+            assert_eq!(tree.search(k), Some(i));
+            assert_eq!(tree.search(k + 59_999), Some(i));
+            assert_eq!(tree.lower_bound(k), i);
+            assert_eq!(tree.key_at(i), k);
+        }
+        assert_eq!(tree.search(keys[0] - 1), None);
+        assert_eq!(tree.lower_bound(i64::MAX), 1000);
+    }
+
+    #[test]
+    fn i64_full_range_keys() {
+        let tree = FastTree64::new(&[i64::MIN, -1, 0, i64::MAX]).unwrap();
+        assert_eq!(tree.search(i64::MIN), Some(0));
+        assert_eq!(tree.search(-2), Some(0));
+        assert_eq!(tree.search(i64::MAX), Some(3));
+        assert_eq!(tree.lower_bound(1), 3);
+        assert_eq!(tree.upper_bound(i64::MAX), 4);
+    }
+
+    #[test]
+    fn i64_generic_methods() {
+        let tree: FastTree<i64> = [30i64, 10, 20, 20].into_iter().collect();
+        assert_eq!(tree.iter().collect::<Vec<_>>(), [10, 20, 20, 30]);
+        assert_eq!(tree.range(15..=20).count(), 2);
+        assert_eq!(tree.index_of(20), Some(1));
+        assert_eq!(tree.clone(), tree);
+        assert!(FastTree64::new(&[3, 1]).is_none());
+        assert!(FastTree64::new(&[]).is_none());
+    }
 }
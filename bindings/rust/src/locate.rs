@@ -0,0 +1,69 @@
+//! Where a key is, or where it would go.
+
+use crate::{FastKey, FastTree};
+
+/// The answer of [`FastTree::locate`]: the key's index if it is present,
+/// or the index it would be inserted at if it is not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Located {
+    /// The key is stored; this is the index of its first occurrence.
+    Present(usize),
+    /// The key is absent; inserting it at this index keeps the keys
+    /// sorted.
+    Absent(usize),
+}
+
+impl Located {
+    /// The index either way: the key's position or its insertion point,
+    /// which is always [`lower_bound`](FastTree::lower_bound).
+    pub fn index(self) -> usize {
+        match self {
+            Located::Present(i) | Located::Absent(i) => i,
+        }
+    }
+
+    /// Whether the key was found.
+    pub fn is_present(self) -> bool {
+        matches!(self, Located::Present(_))
+    }
+}
+
+impl<K: FastKey> FastTree<K> {
+    /// Find `key`, or where it would go: [`binary_search`] with a
+    /// self-describing result.
+    ///
+    /// This is the building block for a sorted overlay over an immutable
+    /// tree, such as an edit log of inserted keys: keep the pending keys
+    /// apart, sorted by their `locate(k).index()`, and merge them in at
+    /// those positions when reading or when rebuilding the tree (for
+    /// instance with [`merge`](FastTree::merge)). A `Present` key is a
+    /// duplicate the overlay can skip or count.
+    ///
+    /// [`binary_search`]: FastTree::binary_search
+    #[must_use]
+    pub fn locate(&self, key: K) -> Located {
+        match self.binary_search(key) {
+            Ok(i) => Located::Present(i),
+            Err(i) => Located::Absent(i),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Located;
+    use crate::FastTree;
+
+    #[test]
+    fn present_and_absent() {
+        let tree = FastTree::new(&[10, 20, 20, 30]).unwrap();
+        assert_eq!(tree.locate(10), Located::Present(0));
+        assert_eq!(tree.locate(20), Located::Present(1));
+        assert_eq!(tree.locate(30), Located::Present(3));
+        assert_eq!(tree.locate(5), Located::Absent(0));
+        assert_eq!(tree.locate(25), Located::Absent(3));
+        assert_eq!(tree.locate(i32::MAX), Located::Absent(4));
+        assert!(tree.locate(20).is_present() && !tree.locate(21).is_present());
+        assert_eq!(tree.locate(21).index(), tree.lower_bound(21));
+    }
+}
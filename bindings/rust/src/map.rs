@@ -0,0 +1,143 @@
+//! A key-to-value map layered over [`FastTree`].
+
+use alloc::vec::Vec;
+
+use crate::FastTree;
+
+/// A map from `i32` keys to values, looked up through a FAST tree.
+///
+/// The tree answers with sorted indices; `values[i]` is the value for the
+/// `i`-th smallest key. Duplicate keys are allowed, each with its own value.
+pub struct FastMap<V> {
+    tree: FastTree,
+    values: Vec<V>,
+}
+
+impl<V> FastMap<V> {
+    /// Build a map from parallel key and value slices, with `keys` sorted.
+    ///
+    /// Returns `None` if `keys` is empty or unsorted.
+    ///
+    /// # Panics
+    /// Panics if `keys` and `values` differ in length.
+    pub fn from_slices(keys: &[i32], values: &[V]) -> Option<Self>
+    where
+        V: Clone,
+    {
+        assert_eq!(keys.len(), values.len(), "FastMap: keys and values differ in length");
+        let tree = FastTree::new(keys)?;
+        Some(FastMap {
+            tree,
+            values: values.to_vec(),
+        })
+    }
+
+    /// Build a map from `(key, value)` pairs sorted by key.
+    ///
+    /// Returns `None` if `pairs` is empty or not sorted by key.
+    pub fn from_pairs(pairs: &[(i32, V)]) -> Option<Self>
+    where
+        V: Clone,
+    {
+        let keys: Vec<i32> = pairs.iter().map(|&(k, _)| k).collect();
+        let tree = FastTree::new(&keys)?;
+        Some(FastMap {
+            tree,
+            values: pairs.iter().map(|(_, v)| v.clone()).collect(),
+        })
+    }
+
+    /// The value for `key`, or `None` if it is absent. With duplicate keys,
+    /// the value of the first occurrence.
+    pub fn get(&self, key: i32) -> Option<&V> {
+        self.tree.index_of(key).map(|i| &self.values[i])
+    }
+
+    /// The value for the largest key <= `key`, or `None` if every key is
+    /// greater. With duplicate keys, the value of the last occurrence.
+    pub fn get_le(&self, key: i32) -> Option<&V> {
+        // The tree's own search may land on any copy of a repeated key;
+        // one before the upper bound is always the last.
+        self.tree.upper_bound(key).checked_sub(1).map(|i| &self.values[i])
+    }
+
+    /// Number of entries.
+    pub fn size(&self) -> usize {
+        self.values.len()
+    }
+
+    /// The underlying tree, for index-based queries.
+    pub fn tree(&self) -> &FastTree {
+        &self.tree
+    }
+
+    /// All values, in key order.
+    pub fn values(&self) -> &[V] {
+        &self.values
+    }
+
+    /// Consume the map, returning its `(key, value)` pairs in key order.
+    ///
+    /// The keys come from the tree's sorted copy and the values are moved,
+    /// not cloned; the tree is then freed once, by its ordinary `Drop`.
+    /// Feeding the result to [`from_pairs`](FastMap::from_pairs) rebuilds an
+    /// equal map.
+    pub fn into_sorted_pairs(self) -> Vec<(i32, V)> {
+        let FastMap { tree, values } = self;
+        tree.into_keys().into_iter().zip(values).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FastMap;
+
+    #[test]
+    fn get_and_get_le() {
+        let map = FastMap::from_pairs(&[(10, "ten"), (20, "twenty"), (30, "thirty")]).unwrap();
+        assert_eq!(map.size(), 3);
+        assert_eq!(map.get(20), Some(&"twenty"));
+        assert_eq!(map.get(25), None);
+        assert_eq!(map.get_le(25), Some(&"twenty"));
+        assert_eq!(map.get_le(30), Some(&"thirty"));
+        assert_eq!(map.get_le(5), None);
+        assert_eq!(map.get_le(i32::MAX), Some(&"thirty"));
+    }
+
+    #[test]
+    fn from_parallel_slices() {
+        let keys = [1, 2, 2, 4];
+        let values = [100, 200, 201, 400];
+        let map = FastMap::from_slices(&keys, &values).unwrap();
+        assert_eq!(map.get(2), Some(&200));
+        assert_eq!(map.get(4), Some(&400));
+        assert_eq!(map.get_le(3), Some(&201));
+        assert_eq!(map.get_le(2), Some(&201));
+        assert_eq!(map.get_le(0), None);
+        assert_eq!(map.values(), values);
+        assert_eq!(map.tree().size(), 4);
+    }
+
+    #[test]
+    fn rejects_unsorted_and_empty() {
+        assert!(FastMap::from_pairs(&[(2, 'b'), (1, 'a')]).is_none());
+        assert!(FastMap::<u8>::from_pairs(&[]).is_none());
+    }
+
+    #[test]
+    fn into_sorted_pairs_round_trip() {
+        let pairs = vec![(1, String::from("a")), (2, "b".into()), (2, "b2".into()), (9, "z".into())];
+        let map = FastMap::from_pairs(&pairs).unwrap();
+        let back = map.into_sorted_pairs();
+        assert_eq!(back, pairs);
+        let rebuilt = FastMap::from_pairs(&back).unwrap();
+        assert_eq!(rebuilt.get(9).map(String::as_str), Some("z"));
+        assert_eq!(rebuilt.values(), ["a", "b", "b2", "z"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "differ in length")]
+    fn slices_length_mismatch() {
+        FastMap::from_slices(&[1, 2], &[1]);
+    }
+}
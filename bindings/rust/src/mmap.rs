@@ -0,0 +1,125 @@
+//! Loading a tree from a memory-mapped file, enabled by the `memmap2`
+//! feature.
+
+use std::fs::File;
+use std::io;
+use std::ops::Deref;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::bytes;
+use crate::{FastKey, FastTree};
+
+/// A tree loaded from a file written with [`FastTree::to_bytes`].
+///
+/// The file uses exactly the `to_bytes` format: a 16-byte header (magic
+/// `b"FAST"`, version, key width, key count) followed by the sorted keys in
+/// little-endian order, so `std::fs::write(path, tree.to_bytes())` produces
+/// a loadable file.
+///
+/// [`open`](FastTreeMmap::open) maps the file and, on little-endian hosts,
+/// hands the mapped key array straight to the C constructor with no
+/// intermediate read or decode. The C library copies the keys into its own
+/// layout while building, so the mapping is released once `open` returns
+/// and later changes to the file do not affect the tree.
+///
+/// It dereferences to [`FastTree`], so every query method is available.
+pub struct FastTreeMmap<K: FastKey = i32> {
+    tree: FastTree<K>,
+}
+
+impl<K: FastKey> FastTreeMmap<K> {
+    /// Map `path` and build a tree from the keys it holds.
+    ///
+    /// A file that is not in the `to_bytes` format, or whose keys are empty
+    /// or unsorted, fails with [`io::ErrorKind::InvalidData`] wrapping the
+    /// [`FastTreeError`](crate::FastTreeError).
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is only read during this call; the usual
+        // caveat applies that another process truncating the file meanwhile
+        // is undefined behavior.
+        let map = unsafe { Mmap::map(&file)? };
+        let body = bytes::key_bytes::<K>(&map).map_err(invalid_data)?;
+
+        let aligned = body.as_ptr().align_offset(std::mem::align_of::<K>()) == 0;
+        let tree = if cfg!(target_endian = "little") && aligned {
+            // SAFETY: `body` is `n * K::WIDTH` bytes, suitably aligned, and
+            // every bit pattern is a valid integer key.
+            let keys = unsafe {
+                std::slice::from_raw_parts(body.as_ptr().cast::<K>(), body.len() / K::WIDTH)
+            };
+            FastTree::try_new(keys)
+        } else {
+            let keys: Vec<K> = body.chunks_exact(K::WIDTH).map(K::read_le).collect();
+            FastTree::try_new(&keys)
+        };
+        tree.map(|tree| FastTreeMmap { tree }).map_err(invalid_data)
+    }
+
+    /// Unwrap into the underlying tree.
+    pub fn into_inner(self) -> FastTree<K> {
+        self.tree
+    }
+}
+
+fn invalid_data(e: crate::FastTreeError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+impl<K: FastKey> Deref for FastTreeMmap<K> {
+    type Target = FastTree<K>;
+
+    fn deref(&self) -> &FastTree<K> {
+        &self.tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FastTreeMmap;
+    use crate::{FastTree, FastTree64};
+    use std::io;
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("fast-tree-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn open_round_trips_to_bytes() {
+        let keys: Vec<i32> = (0..5000).map(|i| i * 3).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        let path = temp_path("i32");
+        std::fs::write(&path, tree.to_bytes()).unwrap();
+        let mapped = FastTreeMmap::<i32>::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(mapped.size(), 5000);
+        assert_eq!(mapped.search(301), Some(100));
+        assert_eq!(mapped.into_inner(), tree);
+    }
+
+    #[test]
+    fn open_i64() {
+        let tree = FastTree64::new(&[-(1 << 50), 0, 1 << 50]).unwrap();
+        let path = temp_path("i64");
+        std::fs::write(&path, tree.to_bytes()).unwrap();
+        let mapped = FastTreeMmap::<i64>::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(mapped.search(1), Some(1));
+    }
+
+    #[test]
+    fn open_rejects_bad_file() {
+        let path = temp_path("bad");
+        std::fs::write(&path, b"not a tree").unwrap();
+        let err = FastTreeMmap::<i32>::open(&path).err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let missing = FastTreeMmap::<i32>::open(temp_path("missing")).err().unwrap();
+        assert_eq!(missing.kind(), io::ErrorKind::NotFound);
+    }
+}
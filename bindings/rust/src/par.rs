@@ -0,0 +1,46 @@
+//! Multi-threaded batch search, enabled by the `rayon` feature.
+
+use rayon::prelude::*;
+
+use crate::{FastKey, FastTree};
+
+/// Queries handed to one rayon task. Large enough that task overhead is
+/// small next to the searches, small enough to balance across cores.
+const PAR_CHUNK: usize = 4096;
+
+impl<K: FastKey + Sync> FastTree<K> {
+    /// Search for each key in `keys` across rayon's thread pool, returning
+    /// the same results as [`search_batch`](FastTree::search_batch).
+    ///
+    /// The queries are split into chunks, each searched with
+    /// [`search_many`](FastTree::search_many) on a worker thread; sharing
+    /// the tree is sound because it is `Sync` and never mutated. Whether
+    /// this beats the single-threaded batch depends on the query count:
+    /// below a few tens of thousands of queries the thread hand-off usually
+    /// costs more than it saves.
+    ///
+    /// Available only with the `rayon` feature, which is off by default.
+    pub fn search_par(&self, keys: &[K]) -> Vec<Option<usize>> {
+        let mut out = vec![None; keys.len()];
+        out.par_chunks_mut(PAR_CHUNK)
+            .zip(keys.par_chunks(PAR_CHUNK))
+            .for_each(|(out, keys)| self.search_many(keys, out));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FastTree;
+
+    #[test]
+    fn search_par_matches_search_many() {
+        let keys: Vec<i32> = (0..50_000).map(|i| i * 2).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        let queries: Vec<i32> = (-100..100_100).step_by(3).collect();
+        let mut expected = vec![None; queries.len()];
+        tree.search_many(&queries, &mut expected);
+        assert_eq!(tree.search_par(&queries), expected);
+        assert!(tree.search_par(&[]).is_empty());
+    }
+}
@@ -0,0 +1,104 @@
+//! Mapping sorted indices back to the caller's original order.
+
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+use crate::{FastKey, FastTree};
+
+impl<K: FastKey> FastTree<K> {
+    /// Build a tree from keys in any order, returning it with the
+    /// permutation from sorted index to original position: key `i` of the
+    /// tree is `keys[perm[i]]`.
+    ///
+    /// The sort is stable, so equal keys keep their original relative
+    /// order. Returns `None` if `keys` is empty or the tree cannot be
+    /// allocated. To keep the permutation with the tree, use
+    /// [`FastTreePermuted`].
+    pub fn with_permutation(keys: &[K]) -> Option<(Self, Vec<usize>)> {
+        let mut perm: Vec<usize> = (0..keys.len()).collect();
+        perm.sort_by_key(|&i| keys[i]);
+        let sorted: Vec<K> = perm.iter().map(|&i| keys[i]).collect();
+        Self::build(&sorted).ok().map(|tree| (tree, perm))
+    }
+}
+
+/// A [`FastTree`] built from unsorted keys that remembers where each key
+/// came from, closing the gap between the sorted indices searches return
+/// and the row ids a caller holds.
+///
+/// It dereferences to [`FastTree`], so every query method is available.
+pub struct FastTreePermuted<K: FastKey = i32> {
+    tree: FastTree<K>,
+    perm: Vec<usize>,
+}
+
+impl<K: FastKey> FastTreePermuted<K> {
+    /// Build from keys in any order, as
+    /// [`FastTree::with_permutation`].
+    pub fn new(keys: &[K]) -> Option<Self> {
+        FastTree::with_permutation(keys).map(|(tree, perm)| FastTreePermuted { tree, perm })
+    }
+
+    /// The position in the original input of the key at `sorted_idx`.
+    ///
+    /// # Panics
+    /// Panics if `sorted_idx >= size()`.
+    pub fn original_index(&self, sorted_idx: usize) -> usize {
+        self.perm[sorted_idx]
+    }
+
+    /// The whole permutation, indexed by sorted position.
+    pub fn permutation(&self) -> &[usize] {
+        &self.perm
+    }
+
+    /// Split into the tree and its permutation.
+    pub fn into_parts(self) -> (FastTree<K>, Vec<usize>) {
+        (self.tree, self.perm)
+    }
+}
+
+impl<K: FastKey> Deref for FastTreePermuted<K> {
+    type Target = FastTree<K>;
+
+    fn deref(&self) -> &FastTree<K> {
+        &self.tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FastTreePermuted;
+    use crate::FastTree;
+
+    #[test]
+    fn shuffled_round_trip() {
+        // i * 37 mod 101 permutes 1..=100, giving a fixed shuffle.
+        let keys: Vec<i32> = (1..=100).map(|i| (i * 37 % 101) * 10).collect();
+        let (tree, perm) = FastTree::with_permutation(&keys).unwrap();
+        assert_eq!(perm.len(), keys.len());
+        for (i, &orig) in perm.iter().enumerate() {
+            assert_eq!(tree.key_at(i), keys[orig]);
+        }
+        let mut seen = perm.clone();
+        seen.sort_unstable();
+        assert_eq!(seen, (0..100).collect::<Vec<_>>());
+
+        let rows = FastTreePermuted::new(&keys).unwrap();
+        for (row, &k) in keys.iter().enumerate() {
+            let sorted = rows.index_of(k).unwrap();
+            assert_eq!(rows.original_index(sorted), row);
+        }
+        assert_eq!(rows.permutation(), perm);
+    }
+
+    #[test]
+    fn equal_keys_keep_input_order() {
+        let rows = FastTreePermuted::new(&[5, 1, 5, 1, 3]).unwrap();
+        assert_eq!(rows.sorted_keys(), [1, 1, 3, 5, 5]);
+        assert_eq!(rows.permutation(), [1, 3, 4, 0, 2]);
+        let (tree, perm) = rows.into_parts();
+        assert_eq!((tree.size(), perm.len()), (5, 5));
+        assert!(FastTree::<i32>::with_permutation(&[]).is_none());
+    }
+}
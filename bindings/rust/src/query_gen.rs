@@ -0,0 +1,155 @@
+//! Deterministic query generators for benchmarking.
+//!
+//! Everything here is seeded and dependency-free, so a seed reproduces the
+//! same query stream on every run and platform. [`Lcg`] is the generator
+//! the cross-language benchmark uses; [`uniform`] reproduces its query
+//! streams exactly, and [`Zipf`] gives the skewed access patterns real
+//! workloads tend to have.
+
+use alloc::vec::Vec;
+
+/// A 64-bit linear congruential generator with Knuth's MMIX constants.
+///
+/// Fast and reproducible rather than statistically strong; fine for
+/// generating queries, not for anything security-related.
+#[derive(Debug, Clone)]
+pub struct Lcg {
+    state: u64,
+}
+
+impl Lcg {
+    /// A generator whose stream is determined by `seed`.
+    pub fn new(seed: u64) -> Self {
+        Lcg { state: seed }
+    }
+
+    /// Advance the state and return it.
+    fn step(&mut self) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.state
+    }
+
+    /// The next 31 random bits (the state's top bits, which have the
+    /// longest period).
+    pub fn next_u31(&mut self) -> u32 {
+        (self.step() >> 33) as u32
+    }
+
+    /// The next value in `0..=max`, exactly as every language benchmark
+    /// computes it.
+    ///
+    /// # Panics
+    /// Panics if `max` is negative.
+    pub fn next_i32_in(&mut self, max: i32) -> i32 {
+        assert!(max >= 0, "Lcg::next_i32_in: negative max {max}");
+        (self.next_u31() as i32).rem_euclid(max.wrapping_add(1))
+    }
+
+    /// The next value in `[0, 1)`, from the state's top 53 bits.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.step() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// `count` queries uniform in `0..=max_key`, the stream every language
+/// benchmark draws for the same seed.
+pub fn uniform(seed: u64, count: usize, max_key: i32) -> Vec<i32> {
+    let mut rng = Lcg::new(seed);
+    (0..count).map(|_| rng.next_i32_in(max_key)).collect()
+}
+
+/// A Zipf distribution over `0..n`: value `i` is drawn with probability
+/// proportional to `1 / (i + 1)^exponent`, so 0 is the most popular.
+///
+/// Sampling is an inversion over a precomputed cumulative table, O(log n)
+/// per draw after O(n) setup and `8n` bytes. Exponents near 1 model typical
+/// key-popularity skew; 0 is uniform. Map the drawn ranks onto keys (for
+/// instance through a shuffled key array) if popular keys should not all
+/// be the smallest ones.
+#[derive(Debug, Clone)]
+pub struct Zipf {
+    cdf: Vec<f64>,
+}
+
+impl Zipf {
+    /// The distribution over `0..n` with the given exponent.
+    ///
+    /// # Panics
+    /// Panics if `n` is 0 or `exponent` is negative or not finite.
+    pub fn new(n: usize, exponent: f64) -> Self {
+        assert!(n > 0, "Zipf::new: empty support");
+        assert!(
+            exponent.is_finite() && exponent >= 0.0,
+            "Zipf::new: invalid exponent {exponent}"
+        );
+        let mut total = 0.0;
+        let cdf = (1..=n)
+            .map(|rank| {
+                total += (rank as f64).powf(-exponent);
+                total
+            })
+            .collect();
+        Zipf { cdf }
+    }
+
+    /// Draw one value in `0..n`.
+    pub fn sample(&self, rng: &mut Lcg) -> usize {
+        let target = rng.next_f64() * self.cdf[self.cdf.len() - 1];
+        self.cdf.partition_point(|&c| c <= target).min(self.cdf.len() - 1)
+    }
+}
+
+/// `count` queries in `0..=max_key` drawn from [`Zipf`] with `exponent`:
+/// small keys are the popular ones.
+///
+/// # Panics
+/// Panics if `max_key` is negative.
+pub fn zipf(seed: u64, count: usize, max_key: i32, exponent: f64) -> Vec<i32> {
+    assert!(max_key >= 0, "query_gen::zipf: negative max_key {max_key}");
+    let dist = Zipf::new(max_key as usize + 1, exponent);
+    let mut rng = Lcg::new(seed);
+    (0..count).map(|_| dist.sample(&mut rng) as i32).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reproducible_for_a_seed() {
+        assert_eq!(uniform(42, 100, 999), uniform(42, 100, 999));
+        assert_ne!(uniform(42, 100, 999), uniform(43, 100, 999));
+        assert_eq!(zipf(7, 100, 999, 1.0), zipf(7, 100, 999, 1.0));
+
+        // First values of the seed-42 stream the other languages produce.
+        let mut state: u64 = 42;
+        for &v in &uniform(42, 4, 2_999_998) {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            assert_eq!(v, ((state >> 33) as i32).rem_euclid(2_999_999));
+        }
+    }
+
+    #[test]
+    fn values_stay_in_range() {
+        assert!(uniform(1, 1000, 9).iter().all(|&v| (0..=9).contains(&v)));
+        assert!(zipf(1, 1000, 9, 1.2).iter().all(|&v| (0..=9).contains(&v)));
+        let mut rng = Lcg::new(3);
+        assert!((0..1000).map(|_| rng.next_f64()).all(|x| (0.0..1.0).contains(&x)));
+        assert_eq!(Lcg::new(5).next_i32_in(0), 0);
+    }
+
+    #[test]
+    fn zipf_is_skewed() {
+        let q = zipf(42, 100_000, 999, 1.0);
+        let count = |k: i32| q.iter().filter(|&&v| v == k).count();
+        // P(0) = 1 / H(1000) ~ 13.4%, and P(0) / P(9) = 10.
+        assert!((12_000..15_000).contains(&count(0)), "{}", count(0));
+        assert!(count(0) > 5 * count(9));
+        assert!(count(9) > count(999));
+    }
+}
@@ -0,0 +1,53 @@
+//! `serde` support, enabled by the `serde` feature.
+//!
+//! The C structure has no portable representation, so a tree serializes as
+//! its sorted key sequence and deserializing rebuilds the tree from it.
+
+use alloc::vec::Vec;
+
+use serde::de::{Deserialize, Deserializer, Error};
+use serde::ser::{Serialize, Serializer};
+
+use crate::{FastKey, FastTree};
+
+impl<K: FastKey + Serialize> Serialize for FastTree<K> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.sorted_keys().serialize(serializer)
+    }
+}
+
+/// Fails with the [`FastTreeError`](crate::FastTreeError) message if the
+/// stored keys are empty or unsorted.
+impl<'de, K: FastKey + Deserialize<'de>> Deserialize<'de> for FastTree<K> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let keys = Vec::<K>::deserialize(deserializer)?;
+        FastTree::try_new(&keys).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FastTree, FastTree64};
+
+    #[test]
+    fn round_trip() {
+        let keys: Vec<i32> = (0..200).map(|i| i * 5 - 300).collect();
+        let tree = FastTree::new(&keys).unwrap();
+        let json = serde_json::to_string(&tree).unwrap();
+        let back: FastTree = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, tree);
+        assert_eq!(back.search(12), tree.search(12));
+
+        let wide = FastTree64::new(&[i64::MIN, 0, i64::MAX]).unwrap();
+        let json = serde_json::to_string(&wide).unwrap();
+        let back: FastTree64 = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, wide);
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        let err = serde_json::from_str::<FastTree>("[3, 1, 2]").unwrap_err();
+        assert!(err.to_string().contains("not sorted"));
+        assert!(serde_json::from_str::<FastTree>("[]").is_err());
+    }
+}
@@ -0,0 +1,151 @@
+//! Opt-in query counters for profiling a [`FastTree`].
+
+use core::ops::Deref;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{FastKey, FastTree};
+
+/// Counts of the queries a [`FastTreeStats`] has served.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct QueryStats {
+    /// Queries that found what they looked for.
+    pub hits: u64,
+    /// Queries that did not.
+    pub misses: u64,
+}
+
+impl QueryStats {
+    /// All counted queries, `hits + misses`.
+    pub fn total(&self) -> u64 {
+        self.hits + self.misses
+    }
+}
+
+/// A [`FastTree`] that counts the queries it serves.
+///
+/// [`search`](FastTreeStats::search), [`lower_bound`](FastTreeStats::lower_bound)
+/// and [`contains`](FastTreeStats::contains) are counted; every other query
+/// reaches the tree through `Deref` uncounted. A query is a hit when
+/// `search` finds a key <= the query, `lower_bound` finds a key >= it, and
+/// `contains` finds the key itself.
+///
+/// The counters are relaxed atomics, so the wrapper stays `Sync` and can be
+/// shared across threads; each counted query pays one uncontended atomic
+/// add. A plain `FastTree` carries no counters and pays nothing, which is
+/// why counting is a separate type rather than a flag.
+pub struct FastTreeStats<K: FastKey = i32> {
+    tree: FastTree<K>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<K: FastKey> FastTree<K> {
+    /// Build a tree that counts its queries from sorted `keys`. Returns
+    /// `None` under the same conditions as [`new`](FastTree::new).
+    pub fn with_stats(keys: &[K]) -> Option<FastTreeStats<K>> {
+        FastTree::new(keys).map(|tree| FastTreeStats {
+            tree,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        })
+    }
+}
+
+impl<K: FastKey> FastTreeStats<K> {
+    /// [`FastTree::search`], counted.
+    pub fn search(&self, key: K) -> Option<usize> {
+        let r = self.tree.search(key);
+        self.count(r.is_some());
+        r
+    }
+
+    /// [`FastTree::lower_bound`], counted.
+    pub fn lower_bound(&self, key: K) -> usize {
+        let r = self.tree.lower_bound(key);
+        self.count(r < self.tree.size());
+        r
+    }
+
+    /// [`FastTree::contains`], counted.
+    pub fn contains(&self, key: K) -> bool {
+        let r = self.tree.contains(key);
+        self.count(r);
+        r
+    }
+
+    /// The counts so far. Under concurrent queries this is a snapshot that
+    /// may lag queries still in flight.
+    pub fn stats(&self) -> QueryStats {
+        QueryStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Zero the counters.
+    pub fn reset_stats(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+
+    /// Unwrap into the underlying tree, dropping the counters.
+    pub fn into_inner(self) -> FastTree<K> {
+        self.tree
+    }
+
+    fn count(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl<K: FastKey> Deref for FastTreeStats<K> {
+    type Target = FastTree<K>;
+
+    fn deref(&self) -> &FastTree<K> {
+        &self.tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueryStats;
+    use crate::FastTree;
+
+    #[test]
+    fn counts_known_sequence() {
+        let tree = FastTree::with_stats(&[10, 20, 30]).unwrap();
+        assert_eq!(tree.stats(), QueryStats::default());
+        assert_eq!(tree.search(5), None);
+        assert_eq!(tree.search(25), Some(1));
+        assert_eq!(tree.lower_bound(31), 3);
+        assert_eq!(tree.lower_bound(30), 2);
+        assert!(tree.contains(20));
+        assert!(!tree.contains(21));
+        assert!(!tree.contains(0));
+        // Uncounted: not one of the three wrapped queries.
+        assert_eq!(tree.rank(20), 1);
+        let stats = tree.stats();
+        assert_eq!((stats.hits, stats.misses, stats.total()), (3, 4, 7));
+        tree.reset_stats();
+        assert_eq!(tree.stats().total(), 0);
+    }
+
+    #[test]
+    fn counts_across_threads() {
+        let keys: Vec<i32> = (0..1000).collect();
+        let tree = FastTree::with_stats(&keys).unwrap();
+        std::thread::scope(|s| {
+            for t in 0..4 {
+                let tree = &tree;
+                s.spawn(move || {
+                    for q in 0..500 {
+                        tree.contains(q * 4 + t);
+                    }
+                });
+            }
+        });
+        // Queries 0..2000; those below 1000 are hits.
+        assert_eq!(tree.stats(), QueryStats { hits: 1000, misses: 1000 });
+    }
+}
@@ -0,0 +1,157 @@
+//! Pure-Rust reference search for validating the FFI, enabled by the
+//! `test-util` feature.
+//!
+//! The reference shares no code with the C library, so agreement between
+//! the two catches layout bugs and ABI mismatches alike; fuzz targets and
+//! regression tests can drive [`FastTree::cross_check`] with arbitrary
+//! queries.
+
+use crate::{FastKey, FastTree};
+
+/// Index of the largest key <= `key` in sorted `keys`, or `None` if every
+/// key is greater: the semantics of [`FastTree::search`], by binary search.
+/// Among duplicates this is the last occurrence.
+pub fn reference_search<K: FastKey>(keys: &[K], key: K) -> Option<usize> {
+    keys.partition_point(|&k| k <= key).checked_sub(1)
+}
+
+/// [`reference_search`] by interpolation search: each probe is placed
+/// where `key` would fall if the keys between the current bounds were
+/// evenly spaced.
+///
+/// On uniformly spread keys this takes O(log log n) probes; on skewed data
+/// it degrades towards a linear scan, which is exactly what comparing it
+/// against [`FastTree::search`] on a real distribution reveals. Key gaps
+/// are taken as unsigned differences and the interpolation product is
+/// formed in 128 bits, so no key values or lengths can overflow it.
+pub fn interpolation_search<K: FastKey>(keys: &[K], key: K) -> Option<usize> {
+    let (&first, &last) = (keys.first()?, keys.last()?);
+    if key < first {
+        return None;
+    }
+    if key >= last {
+        return Some(keys.len() - 1);
+    }
+    // Invariant: keys[lo] <= key < keys[hi].
+    let (mut lo, mut hi) = (0, keys.len() - 1);
+    while hi - lo > 1 {
+        let gap = u128::from(key.distance(keys[lo]));
+        let span = u128::from(keys[hi].distance(keys[lo]));
+        let offset = (gap * (hi - lo) as u128 / span) as usize;
+        let mid = (lo + offset).clamp(lo + 1, hi - 1);
+        if keys[mid] <= key {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Some(lo)
+}
+
+impl<K: FastKey> FastTree<K> {
+    /// Run every query through both the C library's search and
+    /// [`reference_search`], returning the first query on which they
+    /// disagree as `(query, ffi, reference)`.
+    ///
+    /// The C search is called even for trees small enough that
+    /// [`search`](FastTree::search) answers them in Rust, so every tree
+    /// size exercises the FFI.
+    ///
+    /// Indices that differ but point at equal keys agree: the C search may
+    /// land on any occurrence of a duplicated key.
+    pub fn cross_check(&self, queries: &[K]) -> Result<(), (K, Option<usize>, Option<usize>)> {
+        let keys = self.sorted_keys();
+        for &q in queries {
+            let ffi = self.search_ffi(q);
+            let reference = reference_search(keys, q);
+            let agree = match (ffi, reference) {
+                (Some(a), Some(b)) => keys.get(a) == Some(&keys[b]),
+                (a, b) => a == b,
+            };
+            if !agree {
+                return Err((q, ffi, reference));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{interpolation_search, reference_search};
+    use crate::query_gen::Lcg;
+    use crate::{FastTree, FastTree64};
+
+    #[test]
+    fn reference_search_semantics() {
+        let keys = [2, 4, 4, 8];
+        assert_eq!(reference_search(&keys, 1), None);
+        assert_eq!(reference_search(&keys, 4), Some(2));
+        assert_eq!(reference_search(&keys, 7), Some(2));
+        assert_eq!(reference_search(&keys, i32::MAX), Some(3));
+        assert_eq!(reference_search::<i32>(&[], 0), None);
+    }
+
+    #[test]
+    fn randomized_cross_check() {
+        let mut rng = Lcg::new(42);
+        for round in 0..20 {
+            let n = 1 + (u64::from(rng.next_u31()) % 5000) as usize;
+            // Narrow key ranges in odd rounds force many duplicates.
+            let spread = if round % 2 == 0 { u64::from(u32::MAX) } else { 64 };
+            let mut keys: Vec<i32> =
+                (0..n).map(|_| (u64::from(rng.next_u31()) % spread) as i64 as i32).collect();
+            keys.sort_unstable();
+            let tree = FastTree::new(&keys).unwrap();
+            let mut queries: Vec<i32> = (0..2000)
+                .map(|_| (u64::from(rng.next_u31()) % spread) as i64 as i32)
+                .collect();
+            queries.extend([i32::MIN, i32::MAX, keys[0], keys[n - 1]]);
+            assert_eq!(tree.cross_check(&queries), Ok(()), "round {round}");
+        }
+
+        let wide = FastTree64::new(&[-(1 << 40), 0, 1 << 40]).unwrap();
+        assert_eq!(wide.cross_check(&[i64::MIN, -1, 0, 1, i64::MAX]), Ok(()));
+    }
+
+    #[test]
+    fn interpolation_matches_reference() {
+        let mut rng = Lcg::new(7);
+        // Uniform, clustered, exponential and extreme key sets.
+        let mut sets: Vec<Vec<i32>> = vec![
+            (0..1000).map(|i| i * 3).collect(),
+            (0..1000).map(|i| i / 10).collect(),
+            (0..31).map(|i| 1 << i).collect(),
+            vec![i32::MIN, -1, 0, 1, i32::MAX],
+            vec![5],
+        ];
+        let mut random: Vec<i32> = (0..3000).map(|_| rng.next_u31() as i32).collect();
+        random.sort_unstable();
+        sets.push(random);
+        for keys in &sets {
+            let mut queries: Vec<i32> = keys
+                .iter()
+                .flat_map(|&k| [k, k.wrapping_sub(1), k.wrapping_add(1)])
+                .collect();
+            queries.extend([i32::MIN, i32::MAX]);
+            for q in queries {
+                assert_eq!(interpolation_search(keys, q), reference_search(keys, q), "{q}");
+            }
+        }
+        assert_eq!(interpolation_search::<i64>(&[], 0), None);
+        let wide = [i64::MIN, 0, i64::MAX];
+        assert_eq!(interpolation_search(&wide, i64::MAX - 1), Some(1));
+    }
+
+    #[test]
+    fn reports_divergence() {
+        // A tree built from unsorted keys breaks the search contract. More
+        // than TINY_MAX keys, so `search` itself goes through the C library.
+        let keys: Vec<i32> = (0..40).map(|i| (i * 17) % 40 * 5).collect();
+        let tree = FastTree::new_unchecked(&keys).unwrap();
+        assert!(tree.tiny.is_none());
+        let queries: Vec<i32> = (0..210).collect();
+        let (q, ffi, reference) = tree.cross_check(&queries).unwrap_err();
+        assert_ne!(ffi, reference, "query {q}");
+    }
+}
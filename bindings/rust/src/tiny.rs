@@ -0,0 +1,108 @@
+//! The pure-Rust search path for trees of at most [`TINY_MAX`] keys.
+
+use crate::FastKey;
+
+/// Largest tree searched in Rust rather than through the C library.
+///
+/// `bench::run_tiny_crossover` times the fixed-width scan against the FFI
+/// search at widths on both sides of this value; on x86-64 the scan stays
+/// ahead through 32 keys and falls behind by 64. Sixteen leaves a margin
+/// below that crossover and keeps an `i32` scan to one 64-byte block.
+pub(crate) const TINY_MAX: usize = 16;
+
+/// The keys of a tiny tree, padded to `N` with copies of the largest key
+/// so every search scans the same fixed-width block. Trees use
+/// `N = TINY_MAX`; the benchmark instantiates other widths.
+#[derive(Clone, Copy)]
+pub(crate) struct TinyKeys<K, const N: usize = TINY_MAX> {
+    keys: [K; N],
+    len: usize,
+}
+
+impl<K: FastKey, const N: usize> TinyKeys<K, N> {
+    /// A copy of `keys` if they are few enough, else `None`.
+    pub(crate) fn new(keys: &[K]) -> Option<Self> {
+        let (&last, _) = keys.split_last()?;
+        if keys.len() > N {
+            return None;
+        }
+        let mut padded = [last; N];
+        padded[..keys.len()].copy_from_slice(keys);
+        Some(TinyKeys {
+            keys: padded,
+            len: keys.len(),
+        })
+    }
+
+    /// Number of stored keys `<= key`. Padding copies of the last key only
+    /// count when every real key does, so clamping to `len` corrects them.
+    fn count_le(&self, key: K) -> usize {
+        self.keys.iter().filter(|&&k| k <= key).count().min(self.len)
+    }
+
+    /// Index of the last key `<= key`, as `FastTree::search`.
+    #[inline]
+    pub(crate) fn search(&self, key: K) -> Option<usize> {
+        self.count_le(key).checked_sub(1)
+    }
+
+    /// Index of the first key `>= key`, as `FastTree::lower_bound`.
+    #[inline]
+    pub(crate) fn lower_bound(&self, key: K) -> usize {
+        self.keys.iter().filter(|&&k| k < key).count().min(self.len)
+    }
+
+    /// The key at `index`, which must be below `len`.
+    #[inline]
+    pub(crate) fn key(&self, index: usize) -> K {
+        self.keys[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TINY_MAX;
+    use crate::{FastKey, FastTree, FastTree64};
+
+    /// The C library's answers, bypassing the tiny path.
+    fn ffi_search<K: FastKey>(tree: &FastTree<K>, key: K) -> Option<usize> {
+        tree.search_ffi(key)
+    }
+
+    fn ffi_lower_bound<K: FastKey>(tree: &FastTree<K>, key: K) -> usize {
+        tree.lower_bound_ffi(key)
+    }
+
+    #[test]
+    fn matches_ffi_across_threshold() {
+        for n in 1..=TINY_MAX + 4 {
+            for step in [1, 3] {
+                // step 1 with halving gives runs of duplicates.
+                let keys: Vec<i32> = (0..n as i32).map(|i| i * step / 2 * 5 - 20).collect();
+                let tree = FastTree::new(&keys).unwrap();
+                assert_eq!(tree.tiny.is_some(), n <= TINY_MAX);
+                let queries = (-30..keys[n - 1] + 10).chain([i32::MIN, i32::MAX]);
+                for q in queries {
+                    assert_eq!(tree.search(q), ffi_search(&tree, q), "n={n} q={q}");
+                    assert_eq!(tree.lower_bound(q), ffi_lower_bound(&tree, q), "n={n} q={q}");
+                    assert_eq!(tree.search_entry(q).map(|e| e.1), tree.search_le_key(q));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn extremes_and_wide_keys() {
+        let tree = FastTree::new(&[i32::MIN, 0, i32::MAX]).unwrap();
+        assert_eq!(tree.search(i32::MIN), Some(0));
+        assert_eq!(tree.search(i32::MAX), Some(2));
+        assert_eq!(tree.lower_bound(i32::MAX), 2);
+        assert_eq!(tree.lower_bound(i32::MIN), 0);
+
+        let wide = FastTree64::new(&[i64::MIN, -1, i64::MAX]).unwrap();
+        for q in [i64::MIN, -2, -1, 0, i64::MAX] {
+            assert_eq!(wide.search(q), ffi_search(&wide, q));
+            assert_eq!(wide.lower_bound(q), ffi_lower_bound(&wide, q));
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Batch search off the async executor, enabled by the `tokio` feature.
+
+use std::sync::Arc;
+
+use crate::{FastKey, FastTree};
+
+impl<K: FastKey + Send + 'static> FastTree<K> {
+    /// Run [`search_batch`](FastTree::search_batch) on tokio's blocking
+    /// thread pool, so a large CPU-bound batch does not stall the executor
+    /// thread that awaits it.
+    ///
+    /// The tree is taken by `Arc` because the blocking task may outlive any
+    /// borrow; `FastTree` is `Send + Sync`, so one tree can serve many such
+    /// batches from different tasks at once. Handing work to the blocking
+    /// pool costs a thread hand-off, so this is for batches of thousands
+    /// of queries or more; smaller ones are cheaper searched inline.
+    ///
+    /// Must be awaited inside a tokio runtime. A panic in the search is
+    /// propagated to the awaiting task. Available only with the `tokio`
+    /// feature, which is off by default.
+    pub async fn search_batch_spawn(self: Arc<Self>, keys: Vec<K>) -> Vec<Option<usize>> {
+        let task = tokio::task::spawn_blocking(move || self.search_batch(&keys));
+        match task.await {
+            Ok(out) => out,
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(e) => panic!("FastTree::search_batch_spawn: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::FastTree;
+
+    #[test]
+    fn spawn_matches_search_batch() {
+        let keys: Vec<i32> = (0..10_000).map(|i| i * 3).collect();
+        let tree = Arc::new(FastTree::new(&keys).unwrap());
+        let queries: Vec<i32> = (-10..30_010).step_by(7).collect();
+        let expected = tree.search_batch(&queries);
+
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let (a, b) = rt.block_on(async {
+            let a = tokio::spawn(Arc::clone(&tree).search_batch_spawn(queries.clone()));
+            let b = Arc::clone(&tree).search_batch_spawn(queries.clone()).await;
+            (a.await.unwrap(), b)
+        });
+        assert_eq!(a, expected);
+        assert_eq!(b, expected);
+    }
+}
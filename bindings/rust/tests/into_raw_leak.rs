@@ -0,0 +1,49 @@
+//! `into_raw` must not leak the Rust-side copy kept for tiny trees.
+//!
+//! This lives in its own test binary because it installs a counting global
+//! allocator, which would otherwise sit under every unit test.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use fast_tree::FastTree;
+
+/// Counts the bytes each thread has allocated and not yet freed, so the
+/// test harness's own allocations on other threads are not seen.
+struct CountingAlloc;
+
+thread_local! {
+    static LIVE_BYTES: Cell<isize> = const { Cell::new(0) };
+}
+
+fn add_live(delta: isize) {
+    let _ = LIVE_BYTES.try_with(|live| live.set(live.get() + delta));
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        add_live(layout.size() as isize);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        add_live(-(layout.size() as isize));
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+#[test]
+fn into_raw_frees_tiny_copy() {
+    // Warm up once so lazily allocated state is not counted.
+    drop(unsafe { FastTree::<i32>::from_raw(FastTree::new(&[1, 2, 3]).unwrap().into_raw()) });
+    let before = LIVE_BYTES.with(|live| live.get());
+    for _ in 0..10 {
+        // Three keys is well under the 16-key tiny-tree cutoff.
+        let raw = FastTree::new(&[1, 2, 3]).unwrap().into_raw();
+        drop(unsafe { FastTree::<i32>::from_raw(raw) });
+    }
+    assert_eq!(LIVE_BYTES.with(|live| live.get()), before);
+}
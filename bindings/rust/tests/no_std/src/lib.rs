@@ -0,0 +1,31 @@
+//! Compile-only check that `fast-tree` builds without `std`.
+//!
+//! Build it for a target with no `std`, where any use of `std` in the
+//! binding (or a dependency that pulls it in) fails to compile:
+//!
+//! ```text
+//! rustup target add x86_64-unknown-none
+//! cargo build --manifest-path tests/no_std/Cargo.toml --target x86_64-unknown-none
+//! ```
+//!
+//! Nothing here is run or linked, so the C library is not needed.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use fast_tree::{FastMap, FastTree, FastTree64, FastTreeError};
+
+/// Touch the core API, so the check covers what a `no_std` user calls.
+pub fn exercise(keys: &[i32], wide: &[i64]) -> Result<Vec<usize>, FastTreeError> {
+    let tree = FastTree::try_new(keys)?;
+    let copy = FastTree::<i32>::from_bytes(&tree.to_bytes())?;
+    let mut out: Vec<usize> = keys.iter().filter_map(|&k| copy.search(k)).collect();
+    out.push(tree.lower_bound(0));
+    out.extend(FastTree64::try_new(wide)?.search(i64::MAX));
+    let map = FastMap::from_slices(keys, keys).ok_or(FastTreeError::Empty)?;
+    out.extend(map.get_le(0).map(|&v| v as usize));
+    Ok(out)
+}
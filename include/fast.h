@@ -4,6 +4,42 @@
 #include <stddef.h>
 #include <stdint.h>
 
+/*
+ * Symbol prefix.  Building the library with -DFAST_SYMBOL_PREFIX=fast_avx2_
+ * (or the CMake cache variable of the same name) renames every exported
+ * function from fast_<name> to fast_avx2_<name>, so builds tuned for
+ * different instruction sets can be linked into one program.  Code that
+ * includes this header with the same definition keeps calling the plain
+ * names.
+ */
+#ifdef FAST_SYMBOL_PREFIX
+#define FAST_CAT_(a, b) a##b
+#define FAST_CAT(a, b) FAST_CAT_(a, b)
+#define FAST_PREFIXED(name) FAST_CAT(FAST_SYMBOL_PREFIX, name)
+#define fast_create                 FAST_PREFIXED(create)
+#define fast_destroy                FAST_PREFIXED(destroy)
+#define fast_search                 FAST_PREFIXED(search)
+#define fast_search_with_key        FAST_PREFIXED(search_with_key)
+#define fast_search_trace           FAST_PREFIXED(search_trace)
+#define fast_search_batch           FAST_PREFIXED(search_batch)
+#define fast_search_lower_bound     FAST_PREFIXED(search_lower_bound)
+#define fast_size                   FAST_PREFIXED(size)
+#define fast_key_at                 FAST_PREFIXED(key_at)
+#define fast_sorted_keys            FAST_PREFIXED(sorted_keys)
+#define fast_memory_bytes           FAST_PREFIXED(memory_bytes)
+#define fast_levels                 FAST_PREFIXED(levels)
+#define fast_blocking               FAST_PREFIXED(blocking)
+#define fast_create_i64             FAST_PREFIXED(create_i64)
+#define fast_destroy_i64            FAST_PREFIXED(destroy_i64)
+#define fast_search_i64             FAST_PREFIXED(search_i64)
+#define fast_search_with_key_i64    FAST_PREFIXED(search_with_key_i64)
+#define fast_search_lower_bound_i64 FAST_PREFIXED(search_lower_bound_i64)
+#define fast_size_i64               FAST_PREFIXED(size_i64)
+#define fast_key_at_i64             FAST_PREFIXED(key_at_i64)
+#define fast_sorted_keys_i64        FAST_PREFIXED(sorted_keys_i64)
+#define fast_memory_bytes_i64       FAST_PREFIXED(memory_bytes_i64)
+#endif
+
 #ifdef __cplusplus
 extern "C" {
 #endif
@@ -26,6 +62,34 @@ void fast_destroy(fast_tree_t *tree);
  */
 int64_t fast_search(const fast_tree_t *tree, int32_t key);
 
+/*
+ * Point search that also returns the matched key: like fast_search, and on
+ * a hit (result >= 0) stores keys[result] in *out_key.  *out_key is left
+ * untouched on a miss.
+ */
+int64_t fast_search_with_key(const fast_tree_t *tree, int32_t key,
+                             int32_t *out_key);
+
+/*
+ * Traced point search, for teaching and debugging: store the layout offset
+ * of each block the search compares against, root first, in path_out (at
+ * most path_cap entries) and return the number of blocks visited, which
+ * may exceed path_cap.  Queries resolved by the boundary checks (below the
+ * smallest key or at or above the largest) visit none.
+ */
+size_t fast_search_trace(const fast_tree_t *tree, int32_t key,
+                         size_t *path_out, size_t path_cap);
+
+/*
+ * Batch point search: out[i] = fast_search(tree, keys[i]) for i < n.
+ * One call covers the whole query array, so FFI callers cross the
+ * language boundary once instead of once per query.  Queries are walked
+ * down the tree in small interleaved groups, prefetching each one's next
+ * block, so their cache misses overlap.
+ */
+void fast_search_batch(const fast_tree_t *tree, const int32_t *keys, size_t n,
+                       int64_t *out);
+
 /*
  * Lower-bound search: return the index of the first key >= query.
  * Returns (int64_t)fast_size(tree) if query > all keys.
@@ -38,6 +102,45 @@ size_t fast_size(const fast_tree_t *tree);
 /* Return the key at the given index in the original sorted order. */
 int32_t fast_key_at(const fast_tree_t *tree, size_t index);
 
+/*
+ * Return a pointer to the tree's copy of the sorted keys (fast_size(tree)
+ * entries).  The array is owned by the tree and valid until fast_destroy.
+ */
+const int32_t *fast_sorted_keys(const fast_tree_t *tree);
+
+/*
+ * Return the number of heap bytes the tree owns: the blocked node array,
+ * its rank table, the key copy and the tree header.
+ */
+size_t fast_memory_bytes(const fast_tree_t *tree);
+
+/* Return the depth d_N of the padded complete tree (0 for NULL). */
+int fast_levels(const fast_tree_t *tree);
+
+/*
+ * Report the blocking depths of the FAST paper: SIMD blocks of d_K levels,
+ * cache-line blocks of d_L levels and page blocks of d_P levels, each
+ * holding 2^d - 1 keys.  d_P depends on the system page size.
+ */
+void fast_blocking(const fast_tree_t *tree, int *d_k, int *d_l, int *d_p);
+
+/*
+ * 64-bit keys.  Same semantics as the int32_t functions above, over a
+ * separate tree type.
+ */
+typedef struct fast_tree64 fast_tree64_t;
+
+fast_tree64_t *fast_create_i64(const int64_t *keys, size_t n);
+void           fast_destroy_i64(fast_tree64_t *tree);
+int64_t        fast_search_i64(const fast_tree64_t *tree, int64_t key);
+int64_t        fast_search_with_key_i64(const fast_tree64_t *tree, int64_t key,
+                                        int64_t *out_key);
+int64_t        fast_search_lower_bound_i64(const fast_tree64_t *tree, int64_t key);
+size_t         fast_size_i64(const fast_tree64_t *tree);
+int64_t        fast_key_at_i64(const fast_tree64_t *tree, size_t index);
+const int64_t *fast_sorted_keys_i64(const fast_tree64_t *tree);
+size_t         fast_memory_bytes_i64(const fast_tree64_t *tree);
+
 #ifdef __cplusplus
 }
 #endif
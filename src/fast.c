@@ -42,6 +42,30 @@ int64_t fast_search(const fast_tree_t *tree, int32_t key)
     return result;
 }
 
+int64_t fast_search_with_key(const fast_tree_t *tree, int32_t key,
+                             int32_t *out_key)
+{
+    int64_t result = fast_search(tree, key);
+    if (result >= 0)
+        *out_key = tree->keys[result];
+    return result;
+}
+
+void fast_search_batch(const fast_tree_t *tree, const int32_t *keys, size_t n,
+                       int64_t *out)
+{
+    if (!tree || tree->n == 0) {
+        for (size_t i = 0; i < n; i++)
+            out[i] = -1;
+        return;
+    }
+
+    for (size_t i = 0; i < n; i += FAST_BATCH_GROUP) {
+        size_t group = n - i < FAST_BATCH_GROUP ? n - i : FAST_BATCH_GROUP;
+        fast_search_group(tree, keys + i, group, out + i);
+    }
+}
+
 int64_t fast_search_lower_bound(const fast_tree_t *tree, int32_t key)
 {
     if (!tree || tree->n == 0)
@@ -79,3 +103,29 @@ int32_t fast_key_at(const fast_tree_t *tree, size_t index)
         return 0;
     return tree->keys[index];
 }
+
+const int32_t *fast_sorted_keys(const fast_tree_t *tree)
+{
+    return tree ? tree->keys : NULL;
+}
+
+int fast_levels(const fast_tree_t *tree)
+{
+    return tree ? tree->d_n : 0;
+}
+
+void fast_blocking(const fast_tree_t *tree, int *d_k, int *d_l, int *d_p)
+{
+    *d_k = FAST_DK;
+    *d_l = FAST_DL;
+    *d_p = tree ? tree->d_p : 0;
+}
+
+size_t fast_memory_bytes(const fast_tree_t *tree)
+{
+    if (!tree)
+        return 0;
+    return sizeof(*tree)
+         + tree->layout_size * (sizeof(*tree->layout) + sizeof(*tree->sorted_rank))
+         + tree->n * sizeof(*tree->keys);
+}
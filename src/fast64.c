@@ -0,0 +1,229 @@
+#include "fast_internal.h"
+
+/*
+ * FAST tree over 64-bit keys.
+ *
+ * The layout is the same SIMD-blocked permutation the 32-bit tree uses
+ * (see fast_build_permutation), so the traversal below mirrors
+ * fast_search_scalar with int64_t keys.  SSE2 has no 64-bit integer
+ * compare, so there is no vector path yet; an SSE4.2 _mm_cmpgt_epi64
+ * variant would compare two keys per instruction.
+ */
+
+static int build_layout64(struct fast_tree64 *t, const int64_t *sorted_keys,
+                          size_t n)
+{
+    int d_n = fast_tree_depth(n);
+    size_t tree_nodes = ((size_t)1 << d_n) - 1;
+
+    t->d_n = d_n;
+    t->n = n;
+
+    t->keys = (int64_t *)malloc(n * sizeof(int64_t));
+    if (!t->keys)
+        return -1;
+    memcpy(t->keys, sorted_keys, n * sizeof(int64_t));
+
+    int d_p = fast_page_depth(fast_page_size(), sizeof(int64_t));
+    size_t *perm, *bfs_to_sorted;
+    if (fast_build_permutation(d_n, d_p, &perm, &bfs_to_sorted) != 0)
+        goto fail_keys;
+
+    /* Round up to a whole number of cache lines */
+    size_t layout_bytes = ((tree_nodes * sizeof(int64_t) + 63) / 64) * 64;
+    size_t layout_elems = layout_bytes / sizeof(int64_t);
+
+    t->layout = NULL;
+    if (posix_memalign((void **)&t->layout, 64, layout_bytes) != 0)
+        goto fail_perm;
+
+    t->sorted_rank = (int32_t *)malloc(layout_elems * sizeof(int32_t));
+    if (!t->sorted_rank)
+        goto fail_layout;
+    t->layout_size = layout_elems;
+
+    for (size_t i = 0; i < layout_elems; i++) {
+        t->layout[i] = FAST_KEY64_MAX;
+        t->sorted_rank[i] = (int32_t)n;
+    }
+
+    for (size_t i = 0; i < tree_nodes; i++) {
+        size_t sorted_idx = bfs_to_sorted[perm[i]];
+        if (sorted_idx < n) {
+            t->layout[i] = sorted_keys[sorted_idx];
+            t->sorted_rank[i] = (int32_t)sorted_idx;
+        }
+    }
+
+    free(perm);
+    free(bfs_to_sorted);
+    return 0;
+
+fail_layout:
+    free(t->layout);
+    t->layout = NULL;
+fail_perm:
+    free(perm);
+    free(bfs_to_sorted);
+fail_keys:
+    free(t->keys);
+    t->keys = NULL;
+    return -1;
+}
+
+/* Scan forward from a leaf's starting rank, as resolve_simd_leaf does. */
+static int64_t scan_forward64(const struct fast_tree64 *t, int64_t key,
+                              int64_t lo, int steps)
+{
+    const int64_t n = (int64_t)t->n;
+
+    if (lo < -1) lo = -1;
+    if (lo >= n) lo = n - 1;
+    for (int i = 0; i < steps && lo + 1 < n; i++) {
+        if (t->keys[lo + 1] <= key)
+            lo++;
+        else
+            break;
+    }
+    return lo;
+}
+
+static int64_t search64(const struct fast_tree64 *t, int64_t key)
+{
+    const int64_t *tree = t->layout;
+    const int32_t *rank = t->sorted_rank;
+
+    if (key < t->keys[0])
+        return -1;
+    if (key >= t->keys[t->n - 1])
+        return (int64_t)(t->n - 1);
+
+    size_t offset = 0;
+    int depth_remaining = t->d_n;
+    int child_index = 0;
+
+    while (depth_remaining >= FAST_DK) {
+        int64_t k0 = tree[offset];      /* root */
+        int64_t k1 = tree[offset + 1];  /* left child */
+        int64_t k2 = tree[offset + 2];  /* right child */
+
+        if (key <= k0)
+            child_index = (key <= k1) ? 0 : 1;
+        else
+            child_index = (key <= k2) ? 2 : 3;
+
+        depth_remaining -= FAST_DK;
+        if (depth_remaining <= 0) {
+            int64_t lo;
+            switch (child_index) {
+            case 0:  lo = (int64_t)rank[offset + 1] - 1; break;
+            case 1:  lo = (int64_t)rank[offset + 1];      break;
+            case 2:  lo = (int64_t)rank[offset];           break;
+            default: lo = (int64_t)rank[offset + 2];       break;
+            }
+            return scan_forward64(t, key, lo, 3);
+        }
+
+        size_t child_subtree_size = ((size_t)1 << depth_remaining) - 1;
+        offset = offset + FAST_NK + (size_t)child_index * child_subtree_size;
+    }
+
+    /* Single-key leaf (odd depth) */
+    int64_t lo = (key > tree[offset]) ? (int64_t)rank[offset]
+                                      : (int64_t)rank[offset] - 1;
+    return scan_forward64(t, key, lo, 2);
+}
+
+fast_tree64_t *fast_create_i64(const int64_t *keys, size_t n)
+{
+    if (!keys || n == 0)
+        return NULL;
+
+    struct fast_tree64 *t = (struct fast_tree64 *)calloc(1, sizeof(struct fast_tree64));
+    if (!t)
+        return NULL;
+
+    if (build_layout64(t, keys, n) != 0) {
+        free(t);
+        return NULL;
+    }
+
+    return t;
+}
+
+void fast_destroy_i64(fast_tree64_t *tree)
+{
+    if (!tree)
+        return;
+
+    free(tree->layout);
+    free(tree->sorted_rank);
+    free(tree->keys);
+    free(tree);
+}
+
+int64_t fast_search_i64(const fast_tree64_t *tree, int64_t key)
+{
+    if (!tree || tree->n == 0)
+        return -1;
+    return search64(tree, key);
+}
+
+int64_t fast_search_with_key_i64(const fast_tree64_t *tree, int64_t key,
+                                 int64_t *out_key)
+{
+    int64_t result = fast_search_i64(tree, key);
+    if (result >= 0)
+        *out_key = tree->keys[result];
+    return result;
+}
+
+int64_t fast_search_lower_bound_i64(const fast_tree64_t *tree, int64_t key)
+{
+    if (!tree || tree->n == 0)
+        return 0;
+
+    const int64_t *keys = tree->keys;
+    size_t n = tree->n;
+
+    if (key <= keys[0])
+        return 0;
+    if (key > keys[n - 1])
+        return (int64_t)n;
+
+    size_t lo = 0, hi = n - 1;
+    while (lo < hi) {
+        size_t mid = lo + (hi - lo) / 2;
+        if (keys[mid] < key)
+            lo = mid + 1;
+        else
+            hi = mid;
+    }
+    return (int64_t)lo;
+}
+
+size_t fast_size_i64(const fast_tree64_t *tree)
+{
+    return tree ? tree->n : 0;
+}
+
+int64_t fast_key_at_i64(const fast_tree64_t *tree, size_t index)
+{
+    if (!tree || index >= tree->n)
+        return 0;
+    return tree->keys[index];
+}
+
+const int64_t *fast_sorted_keys_i64(const fast_tree64_t *tree)
+{
+    return tree ? tree->keys : NULL;
+}
+
+size_t fast_memory_bytes_i64(const fast_tree64_t *tree)
+{
+    if (!tree)
+        return 0;
+    return sizeof(*tree)
+         + tree->layout_size * (sizeof(*tree->layout) + sizeof(*tree->sorted_rank))
+         + tree->n * sizeof(*tree->keys);
+}
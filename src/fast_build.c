@@ -59,23 +59,24 @@ static void build_inorder_map(size_t *bfs_to_sorted, size_t n)
  *    Contains ceil(d_P/d_L) rounds of cache line blocks.
  *
  * The layout procedure:
- *   layout_block(bfs_tree, out, bfs_root, out_pos, remaining_depth, block_type)
+ *   lay_out_subtree(perm, bfs_root, out_pos, remaining_depth, blocking_level)
  *
  * For a subtree of `depth` levels rooted at BFS index `bfs_root`:
  *   - Extract the top `block_depth` levels (the current block)
  *   - Write those nodes contiguously at `out_pos`
  *   - Recursively lay out each child subtree
+ *
+ * The layout is computed as a permutation of BFS indices (perm[pos] = BFS
+ * node stored at layout position pos) so that it is independent of the key
+ * type; the 32- and 64-bit builders then gather their keys through it.
  */
 
 /*
- * Write a complete binary subtree of `depth` levels rooted at `bfs_root`
- * in BFS order into `out` starting at `out_pos`.
+ * Write the BFS indices of a complete binary subtree of `depth` levels
+ * rooted at `bfs_root`, in BFS order, into `perm` starting at `out_pos`.
  * Returns the number of nodes written (2^depth - 1).
  */
-static size_t write_bfs_block(const int32_t *bfs_tree, int32_t *out,
-                              int32_t *sorted_rank_out,
-                              const size_t *bfs_to_sorted, size_t n_keys,
-                              size_t bfs_root, size_t out_pos,
+static size_t write_bfs_block(size_t *perm, size_t bfs_root, size_t out_pos,
                               int depth, size_t total_bfs_nodes)
 {
     size_t count = 0;
@@ -97,10 +98,7 @@ static size_t write_bfs_block(const int32_t *bfs_tree, int32_t *out,
         size_t node = queue[head++];
         level_remaining--;
 
-        out[out_pos + count] = bfs_tree[node];
-        /* Map layout position to sorted index; sentinel nodes get n_keys */
-        size_t si = bfs_to_sorted[node];
-        sorted_rank_out[out_pos + count] = (si < n_keys) ? (int32_t)si : (int32_t)n_keys;
+        perm[out_pos + count] = node;
         count++;
 
         size_t left = 2 * node + 1;
@@ -186,7 +184,7 @@ static size_t collect_children(size_t bfs_root, int depth,
 /*
  * lay_out_subtree: Recursively arrange a subtree rooted at BFS index
  * `bfs_root` (with `remaining_depth` levels below it) into the output
- * array at position `*out_pos`.
+ * permutation at position `*out_pos`.
  *
  * `block_depth` is the depth of the current blocking level we should
  * decompose into (d_K for SIMD, d_L for cache-line, d_P for page).
@@ -194,10 +192,7 @@ static size_t collect_children(size_t bfs_root, int depth,
  * `blocking_level`: 0=SIMD, 1=cacheline, 2=page
  * `depths`: array [d_K, d_L, d_P]
  */
-static void lay_out_subtree(const int32_t *bfs_tree, int32_t *out,
-                            int32_t *sorted_rank_out,
-                            const size_t *bfs_to_sorted, size_t n_keys,
-                            size_t bfs_root, size_t *out_pos,
+static void lay_out_subtree(size_t *perm, size_t bfs_root, size_t *out_pos,
                             int remaining_depth, int blocking_level,
                             const int *depths, size_t total_bfs_nodes)
 {
@@ -208,9 +203,7 @@ static void lay_out_subtree(const int32_t *bfs_tree, int32_t *out,
 
     if (remaining_depth <= block_depth || blocking_level == 0) {
         int actual_depth = remaining_depth < block_depth ? remaining_depth : block_depth;
-        size_t written = write_bfs_block(bfs_tree, out, sorted_rank_out,
-                                         bfs_to_sorted, n_keys,
-                                         bfs_root, *out_pos,
+        size_t written = write_bfs_block(perm, bfs_root, *out_pos,
                                          actual_depth, total_bfs_nodes);
         *out_pos += written;
 
@@ -219,17 +212,13 @@ static void lay_out_subtree(const int32_t *bfs_tree, int32_t *out,
             size_t nchildren = collect_children(bfs_root, actual_depth,
                                                 children, total_bfs_nodes);
             for (size_t i = 0; i < nchildren; i++) {
-                lay_out_subtree(bfs_tree, out, sorted_rank_out,
-                                bfs_to_sorted, n_keys,
-                                children[i], out_pos,
+                lay_out_subtree(perm, children[i], out_pos,
                                 remaining_depth - actual_depth, blocking_level,
                                 depths, total_bfs_nodes);
             }
         }
     } else {
-        lay_out_subtree(bfs_tree, out, sorted_rank_out,
-                        bfs_to_sorted, n_keys,
-                        bfs_root, out_pos,
+        lay_out_subtree(perm, bfs_root, out_pos,
                         block_depth, blocking_level - 1,
                         depths, total_bfs_nodes);
 
@@ -239,9 +228,7 @@ static void lay_out_subtree(const int32_t *bfs_tree, int32_t *out,
                                             children, total_bfs_nodes);
 
         for (size_t i = 0; i < nchildren; i++) {
-            lay_out_subtree(bfs_tree, out, sorted_rank_out,
-                            bfs_to_sorted, n_keys,
-                            children[i], out_pos,
+            lay_out_subtree(perm, children[i], out_pos,
                             remaining_depth - block_depth, blocking_level,
                             depths, total_bfs_nodes);
         }
@@ -249,34 +236,86 @@ static void lay_out_subtree(const int32_t *bfs_tree, int32_t *out,
     }
 }
 
-int fast_build_layout(struct fast_tree *t, const int32_t *sorted_keys, size_t n)
+int fast_tree_depth(size_t n)
 {
-    /* Compute tree depth: d_N = ceil(log2(n+1)) so that 2^d_N - 1 >= n */
+    /* d_N = ceil(log2(n+1)) so that 2^d_N - 1 >= n */
     int d_n = 0;
-    {
-        size_t tmp = 1;
-        while (tmp - 1 < n) { d_n++; tmp <<= 1; }
+    size_t tmp = 1;
+    while (tmp - 1 < n) { d_n++; tmp <<= 1; }
+    return d_n;
+}
+
+size_t fast_page_size(void)
+{
+    long ps = sysconf(_SC_PAGESIZE);
+    return (ps > 0) ? (size_t)ps : 4096;
+}
+
+int fast_page_depth(size_t page_size, size_t key_bytes)
+{
+    /* Largest d such that (2^d - 1) * key_bytes <= page_size */
+    if (page_size >= 2 * 1024 * 1024 && key_bytes == sizeof(int32_t))
+        return FAST_DP_2M;
+
+    int dp = 1;
+    while (((size_t)1 << (dp + 1)) - 1 <= page_size / key_bytes) {
+        dp++;
     }
+    return dp;
+}
+
+int fast_build_permutation(int d_n, int d_p, size_t **perm_out,
+                           size_t **bfs_to_sorted_out)
+{
+    size_t tree_nodes = ((size_t)1 << d_n) - 1;
+
+    /* Build mapping: bfs_to_sorted[bfs_index] = sorted_index */
+    size_t *bfs_to_sorted = (size_t *)malloc(tree_nodes * sizeof(size_t));
+    if (!bfs_to_sorted)
+        return -1;
+    for (size_t i = 0; i < tree_nodes; i++)
+        bfs_to_sorted[i] = SIZE_MAX;
+
+    build_inorder_map(bfs_to_sorted, tree_nodes);
+
+    size_t *perm = (size_t *)malloc(tree_nodes * sizeof(size_t));
+    if (!perm) {
+        free(bfs_to_sorted);
+        return -1;
+    }
+
+    /* Perform SIMD-blocked layout.
+     *
+     * We use blocking_level=0 (SIMD only) which writes 3-node BFS blocks
+     * with their 4 child subtrees contiguous.  This matches the search
+     * traversal's offset computation: after each 3-key SIMD block,
+     * child i's subtree is at offset + 3 + i * child_subtree_size.
+     *
+     * Cache-line and page blocking (levels 1, 2) would require a search
+     * that tracks nested block offsets; for now SIMD blocking provides
+     * correctness and the main performance benefit (SSE comparisons).
+     */
+    int depths[3] = { FAST_DK, FAST_DL, d_p };
+
+    size_t out_pos = 0;
+    lay_out_subtree(perm, 0, &out_pos, d_n, 0, depths, tree_nodes);
+
+    *perm_out = perm;
+    *bfs_to_sorted_out = bfs_to_sorted;
+    return 0;
+}
+
+int fast_build_layout(struct fast_tree *t, const int32_t *sorted_keys, size_t n)
+{
+    int d_n = fast_tree_depth(n);
     size_t tree_nodes = ((size_t)1 << d_n) - 1;
 
     t->d_n = d_n;
     t->tree_nodes = tree_nodes;
     t->n = n;
 
-    /* Detect page size */
-    long ps = sysconf(_SC_PAGESIZE);
-    t->page_size = (ps > 0) ? (size_t)ps : 4096;
-    /* Compute d_p: largest d such that (2^d - 1) * 4 <= page_size */
-    t->d_p = FAST_DP_4K;
-    if (t->page_size >= 2 * 1024 * 1024) {
-        t->d_p = FAST_DP_2M;
-    } else {
-        int dp = 1;
-        while (((size_t)1 << (dp + 1)) - 1 <= t->page_size / sizeof(int32_t)) {
-            dp++;
-        }
-        t->d_p = dp;
-    }
+    t->page_size = fast_page_size();
+    t->d_p = fast_page_depth(t->page_size, sizeof(int32_t));
     t->n_p = ((size_t)1 << t->d_p) - 1;
 
     /* Copy sorted keys */
@@ -285,40 +324,13 @@ int fast_build_layout(struct fast_tree *t, const int32_t *sorted_keys, size_t n)
         return -1;
     memcpy(t->keys, sorted_keys, n * sizeof(int32_t));
 
-    /* Build BFS tree: bfs_tree[i] holds the key at BFS position i */
-    int32_t *bfs_tree = (int32_t *)malloc(tree_nodes * sizeof(int32_t));
-    if (!bfs_tree) {
-        free(t->keys);
-        t->keys = NULL;
-        return -1;
-    }
-
-    /* Fill with sentinel, then populate via in-order mapping */
-    for (size_t i = 0; i < tree_nodes; i++)
-        bfs_tree[i] = FAST_KEY_MAX;
-
-    /* Build mapping: bfs_to_sorted[bfs_index] = sorted_index */
-    size_t *bfs_to_sorted = (size_t *)malloc(tree_nodes * sizeof(size_t));
-    if (!bfs_to_sorted) {
-        free(bfs_tree);
+    size_t *perm, *bfs_to_sorted;
+    if (fast_build_permutation(d_n, t->d_p, &perm, &bfs_to_sorted) != 0) {
         free(t->keys);
         t->keys = NULL;
         return -1;
     }
-    for (size_t i = 0; i < tree_nodes; i++)
-        bfs_to_sorted[i] = SIZE_MAX;
 
-    build_inorder_map(bfs_to_sorted, tree_nodes);
-
-    /* Populate BFS tree: for each BFS node, assign the key at its
-       in-order rank (or sentinel if rank >= n) */
-    for (size_t i = 0; i < tree_nodes; i++) {
-        size_t sorted_idx = bfs_to_sorted[i];
-        if (sorted_idx < n)
-            bfs_tree[i] = sorted_keys[sorted_idx];
-        else
-            bfs_tree[i] = FAST_KEY_MAX;
-    }
     /* Allocate output layout array (aligned to page boundary for TLB perf) */
     size_t layout_bytes = tree_nodes * sizeof(int32_t);
     /* Round up to multiple of 64 (cache line) and add padding for SSE loads */
@@ -328,8 +340,8 @@ int fast_build_layout(struct fast_tree *t, const int32_t *sorted_keys, size_t n)
     t->layout = NULL;
     if (posix_memalign((void **)&t->layout, t->page_size > 64 ? 4096 : 64,
                        layout_bytes) != 0) {
+        free(perm);
         free(bfs_to_sorted);
-        free(bfs_tree);
         free(t->keys);
         t->keys = NULL;
         return -1;
@@ -340,8 +352,8 @@ int fast_build_layout(struct fast_tree *t, const int32_t *sorted_keys, size_t n)
     if (!t->sorted_rank) {
         free(t->layout);
         t->layout = NULL;
+        free(perm);
         free(bfs_to_sorted);
-        free(bfs_tree);
         free(t->keys);
         t->keys = NULL;
         return -1;
@@ -354,26 +366,17 @@ int fast_build_layout(struct fast_tree *t, const int32_t *sorted_keys, size_t n)
         t->sorted_rank[i] = (int32_t)n;
     }
 
-    /* Perform SIMD-blocked layout.
-     *
-     * We use blocking_level=0 (SIMD only) which writes 3-node BFS blocks
-     * with their 4 child subtrees contiguous.  This matches the search
-     * traversal's offset computation: after each 3-key SIMD block,
-     * child i's subtree is at offset + 3 + i * child_subtree_size.
-     *
-     * Cache-line and page blocking (levels 1, 2) would require a search
-     * that tracks nested block offsets; for now SIMD blocking provides
-     * correctness and the main performance benefit (SSE comparisons).
-     */
-    int depths[3] = { FAST_DK, FAST_DL, t->d_p };
-
-    size_t out_pos = 0;
-    lay_out_subtree(bfs_tree, t->layout, t->sorted_rank,
-                    bfs_to_sorted, n,
-                    0, &out_pos, d_n, 0,
-                    depths, tree_nodes);
+    /* Gather keys through the permutation: each layout slot takes the key
+       at its node's in-order rank, or keeps the sentinel if rank >= n */
+    for (size_t i = 0; i < tree_nodes; i++) {
+        size_t sorted_idx = bfs_to_sorted[perm[i]];
+        if (sorted_idx < n) {
+            t->layout[i] = sorted_keys[sorted_idx];
+            t->sorted_rank[i] = (int32_t)sorted_idx;
+        }
+    }
 
+    free(perm);
     free(bfs_to_sorted);
-    free(bfs_tree);
     return 0;
 }
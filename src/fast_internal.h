@@ -105,9 +105,56 @@ struct fast_tree {
     size_t   page_size;    /* System page size in bytes */
 };
 
-/* Internal functions */
-int  fast_build_layout(struct fast_tree *t, const int32_t *sorted_keys, size_t n);
-void fast_search_sse(const struct fast_tree *t, int32_t key, int64_t *result);
-void fast_search_scalar(const struct fast_tree *t, int32_t key, int64_t *result);
+/*
+ * Internal tree structure for 64-bit keys.  Same SIMD-blocked layout as
+ * struct fast_tree; searched with scalar comparisons, since SSE2 has no
+ * 64-bit integer compare.
+ */
+struct fast_tree64 {
+    int64_t *layout;       /* Hierarchically blocked tree array (aligned) */
+    int32_t *sorted_rank;  /* sorted_rank[i] = index in original sorted array for layout[i] */
+    int64_t *keys;         /* Copy of original sorted keys */
+    size_t   n;            /* Number of actual keys */
+    size_t   layout_size;  /* Number of entries allocated in layout/sorted_rank */
+    int      d_n;          /* Depth of tree (number of levels) */
+};
+
+/* Sentinel value used to pad incomplete 64-bit trees. */
+#define FAST_KEY64_MAX INT64_MAX
+
+/* Internal symbols are prefixed like the public ones (see fast.h). */
+#ifdef FAST_SYMBOL_PREFIX
+#define fast_tree_depth             FAST_PREFIXED(tree_depth)
+#define fast_page_size              FAST_PREFIXED(page_size)
+#define fast_page_depth             FAST_PREFIXED(page_depth)
+#define fast_build_permutation      FAST_PREFIXED(build_permutation)
+#define fast_build_layout           FAST_PREFIXED(build_layout)
+#define fast_search_sse             FAST_PREFIXED(search_sse)
+#define fast_search_scalar          FAST_PREFIXED(search_scalar)
+#define fast_search_group           FAST_PREFIXED(search_group)
+#endif
+
+/*
+ * Internal functions.  fast_build_permutation computes the blocked layout
+ * as perm[layout_pos] = BFS index and bfs_to_sorted[BFS index] = in-order
+ * rank, independent of key width; both arrays are malloc'd for the caller.
+ */
+int    fast_tree_depth(size_t n);
+size_t fast_page_size(void);
+int    fast_page_depth(size_t page_size, size_t key_bytes);
+int    fast_build_permutation(int d_n, int d_p, size_t **perm_out,
+                              size_t **bfs_to_sorted_out);
+int    fast_build_layout(struct fast_tree *t, const int32_t *sorted_keys, size_t n);
+void   fast_search_sse(const struct fast_tree *t, int32_t key, int64_t *result);
+void   fast_search_scalar(const struct fast_tree *t, int32_t key, int64_t *result);
+
+/*
+ * Queries fast_search_group walks in lockstep.  Each one prefetches its next
+ * block while the others compare, so up to this many cache misses overlap.
+ */
+#define FAST_BATCH_GROUP 8
+
+void   fast_search_group(const struct fast_tree *t, const int32_t *keys, size_t n,
+                         int64_t *out);
 
 #endif /* FAST_INTERNAL_H */
@@ -217,3 +217,114 @@ void fast_search_scalar(const struct fast_tree *t, int32_t key, int64_t *result)
     else
         *result = resolve_single_leaf(t, key, offset, child_index);
 }
+
+/* Child index (0-3) of key within the 3-key SIMD block at block. */
+static inline int block_child(const int32_t *block, int32_t key)
+{
+#if FAST_HAVE_SSE
+    __m128i v_cmp = _mm_cmpgt_epi32(_mm_set1_epi32(key),
+                                    _mm_loadu_si128((const __m128i *)block));
+    return FAST_LOOKUP[_mm_movemask_ps(_mm_castsi128_ps(v_cmp)) & 0x7];
+#else
+    if (key <= block[0])
+        return (key <= block[1]) ? 0 : 1;
+    return (key <= block[2]) ? 2 : 3;
+#endif
+}
+
+/*
+ * Interleaved search of n <= FAST_BATCH_GROUP queries.
+ *
+ * Every query in a tree descends through the same sequence of block
+ * depths, so the group advances one SIMD block per query per round.  After
+ * computing a query's child it prefetches that child's block, then moves on
+ * to the next query; by the time the round comes back to it the block is
+ * on its way, and the misses of the whole group overlap instead of being
+ * paid one query after another.  Queries settled by the boundary checks
+ * sit out the traversal.
+ */
+void fast_search_group(const struct fast_tree *t, const int32_t *keys, size_t n,
+                       int64_t *out)
+{
+    const int32_t *tree = t->layout;
+    size_t offset[FAST_BATCH_GROUP];
+    int child[FAST_BATCH_GROUP];
+    size_t live[FAST_BATCH_GROUP];
+    size_t n_live = 0;
+
+    for (size_t i = 0; i < n; i++) {
+        if (keys[i] < t->keys[0]) {
+            out[i] = -1;
+        } else if (t->d_n == 0 || keys[i] >= t->keys[t->n - 1]) {
+            out[i] = (int64_t)(t->n - 1);
+        } else {
+            offset[i] = 0;
+            live[n_live++] = i;
+        }
+    }
+    if (n_live == 0)
+        return;
+
+    int depth_remaining = t->d_n;
+    while (depth_remaining >= FAST_DK) {
+        depth_remaining -= FAST_DK;
+        size_t child_subtree_size = ((size_t)1 << depth_remaining) - 1;
+        for (size_t j = 0; j < n_live; j++) {
+            size_t i = live[j];
+            child[i] = block_child(tree + offset[i], keys[i]);
+            if (depth_remaining > 0) {
+                offset[i] += FAST_NK + (size_t)child[i] * child_subtree_size;
+                __builtin_prefetch(tree + offset[i]);
+            }
+        }
+    }
+
+    for (size_t j = 0; j < n_live; j++) {
+        size_t i = live[j];
+        if (depth_remaining == 0) {
+            out[i] = resolve_simd_leaf(t, keys[i], offset[i], child[i]);
+        } else {
+            /* One level left: a single-key leaf. */
+            int c = (keys[i] > tree[offset[i]]) ? 1 : 0;
+            out[i] = resolve_single_leaf(t, keys[i], offset[i], c);
+        }
+    }
+}
+
+/*
+ * Traced search: the scalar traversal again, recording the layout offset of
+ * each block (3-key SIMD block or trailing single key) it compares against.
+ * Queries outside [keys[0], keys[n-1]) are resolved by the boundary checks
+ * and visit no blocks.
+ */
+size_t fast_search_trace(const fast_tree_t *t, int32_t key, size_t *path_out,
+                         size_t path_cap)
+{
+    if (!t || t->d_n == 0 || key < t->keys[0] || key >= t->keys[t->n - 1])
+        return 0;
+
+    const int32_t *tree = t->layout;
+    size_t offset = 0;
+    size_t visited = 0;
+    int depth_remaining = t->d_n;
+
+    while (depth_remaining > 0) {
+        if (visited < path_cap)
+            path_out[visited] = offset;
+        visited++;
+
+        if (depth_remaining < FAST_DK)
+            break;
+
+        int child_index;
+        if (key <= tree[offset])
+            child_index = (key <= tree[offset + 1]) ? 0 : 1;
+        else
+            child_index = (key <= tree[offset + 2]) ? 2 : 3;
+
+        depth_remaining -= FAST_DK;
+        size_t child_subtree_size = ((size_t)1 << depth_remaining) - 1;
+        offset = offset + FAST_NK + (size_t)child_index * child_subtree_size;
+    }
+    return visited;
+}
@@ -184,6 +184,161 @@ static void test_lower_bound(void)
     fast_destroy(t);
 }
 
+static void test_search_batch(void)
+{
+    TEST("search_batch: matches scalar search");
+    int32_t keys[500];
+    for (int i = 0; i < 500; i++) keys[i] = i * 4;
+    fast_tree_t *t = fast_create(keys, 500);
+    assert(t != NULL);
+    int32_t queries[1000];
+    int64_t out[1000];
+    for (int i = 0; i < 1000; i++) queries[i] = i * 3 - 50;
+    fast_search_batch(t, queries, 1000, out);
+    int ok = 1;
+    for (int i = 0; i < 1000; i++)
+        if (out[i] != fast_search(t, queries[i])) { ok = 0; break; }
+    if (ok) PASS(); else FAIL("mismatch");
+    fast_destroy(t);
+
+    /* Sizes giving odd and even depths, and a partial last group. */
+    TEST("search_batch: every depth, ragged groups");
+    ok = 1;
+    for (int n = 1; n <= 300 && ok; n += 7) {
+        for (int i = 0; i < n; i++) keys[i] = i / 2 * 5;
+        t = fast_create(keys, (size_t)n);
+        assert(t != NULL);
+        for (int i = 0; i < 1000; i++) queries[i] = i * 11 % (n * 3 + 7) - 3;
+        fast_search_batch(t, queries, 997, out);
+        for (int i = 0; i < 997; i++)
+            if (out[i] != fast_search(t, queries[i])) { ok = 0; break; }
+        fast_destroy(t);
+    }
+    if (ok) PASS(); else FAIL("mismatch");
+
+    TEST("search_batch: NULL tree yields -1");
+    fast_search_batch(NULL, queries, 3, out);
+    if (out[0] == -1 && out[1] == -1 && out[2] == -1) PASS();
+    else FAIL("expected -1");
+}
+
+static void test_search_with_key(void)
+{
+    TEST("search_with_key: index and key in one call");
+    int32_t keys[] = {5, 10, 10, 20, 40};
+    fast_tree_t *t = fast_create(keys, 5);
+    assert(t != NULL);
+    int ok = 1;
+    for (int32_t q = 0; q < 50; q++) {
+        int32_t k = -1;
+        int64_t r = fast_search_with_key(t, q, &k);
+        if (r != fast_search(t, q)) ok = 0;
+        if (r >= 0 && k != keys[r]) ok = 0;
+        if (r < 0 && k != -1) ok = 0;
+    }
+    if (ok) PASS(); else FAIL("mismatch");
+    fast_destroy(t);
+}
+
+static void test_search_trace(void)
+{
+    TEST("search_trace: one block per SIMD level");
+    int32_t keys[1000];
+    for (int i = 0; i < 1000; i++) keys[i] = i * 2;
+    fast_tree_t *t = fast_create(keys, 1000);
+    assert(t != NULL);
+    size_t path[16];
+    /* d_N = 10: five 3-key blocks, root block at offset 0 */
+    size_t len = fast_search_trace(t, 777, path, 16);
+    int ok = len == 5 && path[0] == 0;
+    for (size_t i = 1; ok && i < len; i++)
+        if (path[i] <= path[i - 1]) ok = 0;
+    ok = ok && fast_search_trace(t, 777, path, 2) == 5;
+    ok = ok && fast_search_trace(t, -1, path, 16) == 0;
+    ok = ok && fast_search_trace(t, 1998, path, 16) == 0;
+    if (ok) PASS(); else FAIL("unexpected path");
+    fast_destroy(t);
+}
+
+static void test_sorted_keys(void)
+{
+    TEST("sorted_keys: matches input order");
+    int32_t keys[] = {3, 3, 8, 21, 40, 41, 99};
+    fast_tree_t *t = fast_create(keys, 7);
+    assert(t != NULL);
+    const int32_t *view = fast_sorted_keys(t);
+    if (view && memcmp(view, keys, sizeof(keys)) == 0) PASS();
+    else FAIL("keys differ");
+    fast_destroy(t);
+
+    TEST("sorted_keys: NULL tree");
+    if (fast_sorted_keys(NULL) == NULL) PASS(); else FAIL("expected NULL");
+}
+
+static void test_memory_bytes(void)
+{
+    TEST("memory_bytes: layout + keys, grows with n");
+    int32_t small[15], big[1000];
+    for (int i = 0; i < 15; i++) small[i] = i;
+    for (int i = 0; i < 1000; i++) big[i] = i;
+    fast_tree_t *a = fast_create(small, 15);
+    fast_tree_t *b = fast_create(big, 1000);
+    assert(a && b);
+    size_t ma = fast_memory_bytes(a), mb = fast_memory_bytes(b);
+    /* At least keys + one layout slot and one rank per key */
+    if (ma >= 15 * 3 * sizeof(int32_t) && mb >= 1000 * 3 * sizeof(int32_t) &&
+        mb > ma && fast_memory_bytes(NULL) == 0) PASS();
+    else FAIL("unexpected sizes");
+    fast_destroy(a);
+    fast_destroy(b);
+}
+
+static void test_levels(void)
+{
+    TEST("levels: depth of padded tree, blocking depths");
+    int32_t keys[1000];
+    for (int i = 0; i < 1000; i++) keys[i] = i;
+    fast_tree_t *one = fast_create(keys, 1);
+    fast_tree_t *big = fast_create(keys, 1000);
+    assert(one && big);
+    int d_k, d_l, d_p;
+    fast_blocking(big, &d_k, &d_l, &d_p);
+    /* 1000 keys need depth 10 (2^10 - 1 = 1023 slots) */
+    if (fast_levels(one) == 1 && fast_levels(big) == 10 &&
+        fast_levels(NULL) == 0 && d_k == 2 && d_l == 4 && d_p >= d_l) PASS();
+    else FAIL("unexpected depths");
+    fast_destroy(one);
+    fast_destroy(big);
+}
+
+static void test_i64(void)
+{
+    TEST("i64: exact matches and gaps");
+    int64_t keys[100];
+    for (int i = 0; i < 100; i++)
+        keys[i] = (int64_t)i * 5000000000LL - 100000000000LL;
+    fast_tree64_t *t = fast_create_i64(keys, 100);
+    assert(t != NULL);
+    assert(fast_size_i64(t) == 100);
+    int ok = 1;
+    for (int i = 0; i < 100; i++) {
+        if (fast_search_i64(t, keys[i]) != i) { ok = 0; break; }
+        if (fast_search_i64(t, keys[i] + 1) != i) { ok = 0; break; }
+        if (fast_key_at_i64(t, (size_t)i) != keys[i]) { ok = 0; break; }
+    }
+    if (ok) PASS(); else FAIL("mismatch");
+
+    TEST("i64: out of range, lower_bound, sorted_keys");
+    if (fast_search_i64(t, INT64_MIN) == -1 &&
+        fast_search_i64(t, INT64_MAX) == 99 &&
+        fast_search_lower_bound_i64(t, keys[10] - 1) == 10 &&
+        fast_search_lower_bound_i64(t, INT64_MAX) == 100 &&
+        memcmp(fast_sorted_keys_i64(t), keys, sizeof(keys)) == 0) PASS();
+    else FAIL("boundary mismatch");
+
+    fast_destroy_i64(t);
+}
+
 static int cmp_int32(const void *a, const void *b)
 {
     int32_t x = *(const int32_t *)a, y = *(const int32_t *)b;
@@ -257,6 +412,13 @@ int main(void)
     test_non_power_of_two();
     test_duplicates();
     test_lower_bound();
+    test_search_batch();
+    test_search_with_key();
+    test_search_trace();
+    test_sorted_keys();
+    test_memory_bytes();
+    test_levels();
+    test_i64();
     test_large_random();
 
     printf("\n%d / %d tests passed\n", tests_passed, tests_run);